@@ -0,0 +1,86 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tbl::{Block, Bound, ColorLevel, RenderBlock, Renderer};
+
+/// `count` segments separated by spaces, on a single lane.
+fn intervals(count: usize) -> Vec<Bound> {
+    (0..count)
+        .map(|i| (2. * i as f64, 2. * i as f64 + 1.))
+        .collect()
+}
+
+fn bench_block_count(c: &mut Criterion) {
+    let mut group = c.benchmark_group("block count");
+    for count in [1_000, 2_000, 4_000, 8_000].iter() {
+        let data = intervals(*count);
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &data, |b, data| {
+            b.iter(|| {
+                Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+                    .with_length(2 * data.len())
+                    .with_color_level(ColorLevel::Monochrome)
+                    .render()
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_lane_height(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lane height");
+    let data = intervals(1_000);
+    for height in [1, 4, 16, 64].iter() {
+        let render = |b: &Block<String>| match b {
+            Block::Space(w) => RenderBlock::Space(" ".repeat(*w)),
+            Block::Segment(w, _) => RenderBlock::MultiLineBlock(vec!["=".repeat(*w); *height]),
+        };
+        group.throughput(Throughput::Elements(*height as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(height), &data, |b, data| {
+            b.iter(|| {
+                Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+                    .with_length(2 * data.len())
+                    .with_renderer(&render)
+                    .with_color_level(ColorLevel::Monochrome)
+                    .render()
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// `count` overlapping intervals, one per lane.
+#[cfg(feature = "rayon")]
+fn bench_lane_count(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lane count");
+    for count in [1_000, 4_000].iter() {
+        let data: Vec<Bound> = (0..*count).map(|i| (i as f64, 1_000. + i as f64)).collect();
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::new("render", count), &data, |b, data| {
+            let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>);
+            let renderer = renderer
+                .with_length(200)
+                .with_color_level(ColorLevel::Monochrome);
+            b.iter(|| renderer.render().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("render_par", count), &data, |b, data| {
+            let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>);
+            let renderer = renderer
+                .with_length(200)
+                .with_color_level(ColorLevel::Monochrome);
+            b.iter(|| renderer.render_par().unwrap())
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "rayon"))]
+criterion_group!(benches, bench_block_count, bench_lane_height);
+#[cfg(feature = "rayon")]
+criterion_group!(
+    benches,
+    bench_block_count,
+    bench_lane_height,
+    bench_lane_count
+);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+use chrono::{TimeZone, Utc};
+use tbl::{CalendarTime, Renderer, Shading, TBLError};
+
+fn main() -> Result<(), TBLError<String>> {
+    // on-call shifts in July 2020
+    let at = |d, h| {
+        Utc.with_ymd_and_hms(2020, 7, d, h, 0, 0)
+            .unwrap()
+            .timestamp() as f64
+    };
+    let data = vec![
+        (at(1, 9), at(1, 17), "alice"),
+        (at(2, 9), at(3, 9), "bob"),
+        (at(6, 0), at(8, 12), "alice"),
+        (at(9, 18), at(9, 22), "carol"),
+        (at(13, 9), at(13, 12), "bob"),
+        (at(15, 0), at(20, 0), "carol"),
+        (at(22, 8), at(22, 20), "alice"),
+        (at(27, 9), at(27, 13), "bob"),
+        (at(29, 12), at(31, 0), "carol"),
+    ];
+    let month = (
+        at(1, 0),
+        Utc.with_ymd_and_hms(2020, 8, 1, 0, 0, 0)
+            .unwrap()
+            .timestamp() as f64,
+    );
+    let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+        Some(l.to_string())
+    });
+    let renderer = renderer.with_boundaries(month).with_bound_format(&|t| {
+        Utc.timestamp_opt(t as i64, 0)
+            .unwrap()
+            .format("%b %d")
+            .to_string()
+    });
+    for line in renderer.render_calendar(Shading::Coverage, CalendarTime::default())? {
+        println!("{}", line);
+    }
+    Ok(())
+}
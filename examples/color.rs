@@ -1,25 +1,15 @@
-use tbl::{Block, RenderBlock, Renderer, TBLError};
-use termion::color;
+use tbl::{Block, Color, RenderBlock, Renderer, Span, Style, TBLError};
 
 fn render(b: &Block<String>) -> RenderBlock {
     match b {
-        Block::Space(length) => RenderBlock::Space(format!(
-            "{}{}{}",
-            color::Bg(color::Black),
+        Block::Space(length) => RenderBlock::StyledSpace(vec![vec![Span::styled(
             " ".repeat(*length),
-            color::Bg(color::Reset)
-        )),
-        Block::Segment(length, label) => {
-            let mut truncated = label.clone().unwrap_or_default();
-            truncated.truncate(*length);
-            RenderBlock::Block(format!(
-                "{}{}{}{}",
-                color::Bg(color::LightGreen),
-                truncated,
-                " ".repeat(*length - truncated.len()),
-                color::Bg(color::Reset),
-            ))
-        }
+            Style::new().bg(Color::Black),
+        )]]),
+        Block::Segment(_length, label) => RenderBlock::StyledBlock(vec![vec![Span::styled(
+            label.clone().unwrap_or_default(),
+            Style::new().bg(Color::BrightGreen),
+        )]]),
     }
 }
 
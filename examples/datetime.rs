@@ -1,15 +1,14 @@
 use chrono::{DateTime, Local, TimeZone, Utc};
 use itertools::zip;
-use tbl::{Block, Bound, RenderBlock, Renderer, TBLError};
-use termion::color;
+use tbl::{Block, Bound, Color, RenderBlock, Renderer, Span, Style, TBLError};
 
-type RGB = (u8, u8, u8);
-type Label = (String, RGB);
+type Rgb = (u8, u8, u8);
+type Label = (String, Rgb);
 
 struct Activity {
     start: DateTime<Local>,
     end: DateTime<Local>,
-    label: Option<(String, RGB)>,
+    label: Option<(String, Rgb)>,
 }
 
 fn fbounds(activity: &Activity) -> Bound {
@@ -19,40 +18,30 @@ fn fbounds(activity: &Activity) -> Bound {
     )
 }
 
-fn label_activity(activity: &Activity) -> Option<(String, RGB)> {
+fn label_activity(activity: &Activity) -> Option<(String, Rgb)> {
     activity.label.clone()
 }
 
-fn label_legend(activity: &Activity) -> Option<(String, RGB)> {
+fn label_legend(activity: &Activity) -> Option<(String, Rgb)> {
     Some((
         format!(
             "{}-{}",
-            activity.start.format("%H:%M").to_string(),
+            activity.start.format("%H:%M"),
             activity.end.format("%H:%M")
         ),
         (96, 125, 139),
     ))
 }
 
-fn render(b: &Block<(String, RGB)>) -> RenderBlock {
+fn render(b: &Block<(String, Rgb)>) -> RenderBlock {
     match b {
-        Block::Space(length) => RenderBlock::Space(format!(
-            "{}{}{}",
-            color::Bg(color::Reset),
-            " ".repeat(*length),
-            color::Bg(color::Reset)
-        )),
-        Block::Segment(length, label) => {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(_length, label) => {
             let (label, (r, g, b)) = label.clone().unwrap_or_else(|| ("".to_string(), (0, 0, 0)));
-            let mut truncated = label.clone();
-            truncated.truncate(*length);
-            RenderBlock::Block(format!(
-                "{}{}{}{}",
-                color::Bg(color::Rgb(r, g, b)),
-                truncated,
-                " ".repeat(*length - truncated.len()),
-                color::Bg(color::Reset),
-            ))
+            RenderBlock::StyledBlock(vec![vec![Span::styled(
+                label,
+                Style::new().bg(Color::Rgb(r, g, b)),
+            )]])
         }
     }
 }
@@ -61,31 +50,31 @@ fn main() -> Result<(), TBLError<Label>> {
     // this isn't the real Apollo 11 timeline, it's just an example ;-)
     let data = vec![
         Activity {
-            start: Utc.ymd(1969, 7, 20).and_hms(8, 0, 0).into(),
-            end: Utc.ymd(1969, 7, 20).and_hms(9, 20, 0).into(),
+            start: Utc.with_ymd_and_hms(1969, 7, 20, 8, 0, 0).unwrap().into(),
+            end: Utc.with_ymd_and_hms(1969, 7, 20, 9, 20, 0).unwrap().into(),
             label: Some(("breakfast".to_string(), (139, 195, 74))),
         },
         Activity {
-            start: Utc.ymd(1969, 7, 20).and_hms(9, 30, 0).into(),
-            end: Utc.ymd(1969, 7, 20).and_hms(11, 0, 0).into(),
+            start: Utc.with_ymd_and_hms(1969, 7, 20, 9, 30, 0).unwrap().into(),
+            end: Utc.with_ymd_and_hms(1969, 7, 20, 11, 0, 0).unwrap().into(),
             label: Some(("launch".to_string(), (255, 152, 0))),
         },
         Activity {
-            start: Utc.ymd(1969, 7, 20).and_hms(12, 0, 0).into(),
-            end: Utc.ymd(1969, 7, 20).and_hms(19, 0, 0).into(),
+            start: Utc.with_ymd_and_hms(1969, 7, 20, 12, 0, 0).unwrap().into(),
+            end: Utc.with_ymd_and_hms(1969, 7, 20, 19, 0, 0).unwrap().into(),
             label: Some((
                 "orbit the moon and count craters".to_string(),
                 (3, 169, 244),
             )),
         },
         Activity {
-            start: Utc.ymd(1969, 7, 20).and_hms(14, 0, 0).into(),
-            end: Utc.ymd(1969, 7, 20).and_hms(15, 0, 0).into(),
+            start: Utc.with_ymd_and_hms(1969, 7, 20, 14, 0, 0).unwrap().into(),
+            end: Utc.with_ymd_and_hms(1969, 7, 20, 15, 0, 0).unwrap().into(),
             label: Some(("eat re-hydrated food".to_string(), (3, 169, 244))),
         },
         Activity {
-            start: Utc.ymd(1969, 7, 20).and_hms(20, 17, 0).into(),
-            end: Utc.ymd(1969, 7, 20).and_hms(22, 0, 0).into(),
+            start: Utc.with_ymd_and_hms(1969, 7, 20, 20, 17, 0).unwrap().into(),
+            end: Utc.with_ymd_and_hms(1969, 7, 20, 22, 0, 0).unwrap().into(),
             label: Some(("moon walk".to_string(), (96, 125, 139))),
         },
     ];
@@ -0,0 +1,49 @@
+use chrono::{TimeZone, Utc};
+use std::env;
+use tbl::{Assignment, Palette, Renderer, TBLError};
+
+type Label = (String, String); // (activity, crew member)
+
+fn main() -> Result<(), TBLError<Label>> {
+    let at = |h, m| {
+        Utc.with_ymd_and_hms(1969, 7, 20, h, m, 0)
+            .unwrap()
+            .timestamp() as f64
+    };
+    let data = vec![
+        (at(8, 0), at(9, 20), "breakfast", "Armstrong"),
+        (at(8, 10), at(9, 0), "breakfast", "Aldrin"),
+        (at(9, 30), at(11, 0), "launch", "Armstrong"),
+        (at(9, 30), at(11, 0), "launch", "Aldrin"),
+        (at(12, 0), at(19, 0), "orbit the moon", "Collins"),
+        (at(14, 0), at(15, 0), "eat re-hydrated food", "Collins"),
+        (at(20, 17), at(22, 0), "moon walk", "Armstrong"),
+        (at(20, 36), at(22, 0), "moon walk", "Aldrin"),
+    ];
+    let mut renderer = Renderer::new(
+        data.as_slice(),
+        &|&(start, end, _, _)| (start, end),
+        &|&(_, _, activity, member)| Some((activity.to_string(), member.to_string())),
+    );
+    let renderer = renderer
+        .with_length(120)
+        .with_groups(&|(_, member)| Some(member.clone()))
+        .with_palette(
+            Palette::colorblind_safe(Assignment::FirstAppearance),
+            &|(activity, _)| activity.clone(),
+        )
+        .with_label_format(&|(activity, _)| activity.clone())
+        .with_bound_format(&|t| {
+            Utc.timestamp_opt(t as i64, 0)
+                .unwrap()
+                .format("%H:%M")
+                .to_string()
+        })
+        .with_duration_format(&|d| format!("{}min", (d / 60.).round()));
+    match env::args().nth(1).as_deref() {
+        Some("html") => print!("{}", renderer.render_html()?),
+        Some("md") => print!("{}", renderer.render_markdown()?),
+        _ => print!("{}", renderer.render_svg()?),
+    }
+    Ok(())
+}
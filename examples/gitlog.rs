@@ -0,0 +1,50 @@
+use std::error::Error;
+use tbl::gitlog::{self, Session};
+use tbl::{Block, RenderBlock, Renderer};
+
+// git log --format='%at%x09%an%x09%s' of a day (2024-05-01, UTC)
+const LOG: &str = "\
+1714586400\tGrace\tRelease 1.2
+1714579200\tAda\tDocument options
+1714575600\tGrace\tFix wrapping
+1714566600\tAda\tAdd palette legend
+1714563000\tAda\tAdd palette
+1714557600\tGrace\tBump dependencies
+1714554000\tAda\tRefactor layout
+";
+
+fn render(b: &Block<Session>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, session) => {
+            let label: String = session
+                .as_ref()
+                .map(|s| s.author.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - label.chars().count());
+            RenderBlock::Block(label + &padding)
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let commits = gitlog::parse(LOG)?;
+    // commits less than 2 hours apart are a session
+    let sessions = gitlog::sessions(&commits, 7200.);
+    // single commit sessions are drawn 30 minutes wide
+    let bounds = |s: &Session| match s.bounds {
+        (start, end) if start == end => (start, start + 1800.),
+        bounds => bounds,
+    };
+    let mut renderer = Renderer::new(sessions.as_slice(), &bounds, &|s| Some(s.clone()));
+    // one swimlane per author
+    let author = |s: &Session| Some(s.author.clone());
+    let renderer = renderer
+        .with_length(80)
+        .with_groups(&author)
+        .with_renderer(&render);
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use tbl::ics::{self, IcsEvent};
+use tbl::{Block, RenderBlock, Renderer};
+
+// an on-call schedule and a meeting, e.g. exported from a calendar application
+const CALENDAR: &str = "BEGIN:VCALENDAR
+X-WR-CALNAME:Platform
+BEGIN:VEVENT
+SUMMARY:On-call
+DTSTART;VALUE=DATE:20240506
+DTEND;VALUE=DATE:20240509
+RRULE:FREQ=WEEKLY;INTERVAL=2
+ATTENDEE;CN=Alice:mailto:alice@example.com
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:On-call
+DTSTART;VALUE=DATE:20240509
+DTEND;VALUE=DATE:20240513
+RRULE:FREQ=WEEKLY;INTERVAL=2
+ATTENDEE;CN=Bob:mailto:bob@example.com
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Workshop
+DTSTART;TZID=America/New_York:20240510T090000
+DURATION:PT8H
+RRULE:FREQ=WEEKLY;BYDAY=FR
+ATTENDEE;CN=Alice:mailto:alice@example.com
+ATTENDEE;CN=Bob:mailto:bob@example.com
+END:VEVENT
+END:VCALENDAR
+";
+
+fn render(b: &Block<IcsEvent>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, event) => {
+            let summary: String = event
+                .as_ref()
+                .map(|e| e.summary.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - summary.chars().count());
+            RenderBlock::Block(summary + &padding)
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // May 6th to May 20th 2024 (UTC)
+    let window = (1_714_953_600., 1_716_163_200.);
+    let events = ics::by_attendee(ics::parse(CALENDAR, Some(window))?);
+    let mut renderer = Renderer::new(events.as_slice(), &|e| e.bounds, &|e| Some(e.clone()));
+    // one swimlane per attendee
+    let attendee = |e: &IcsEvent| e.attendees.first().cloned();
+    let renderer = renderer
+        .with_length(84)
+        .with_boundaries(window)
+        .with_groups(&attendee)
+        .with_renderer(&render);
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::thread;
+use std::time::Duration;
+use tbl::{TBLError, TimelineLayer};
+use tracing::info_span;
+use tracing_subscriber::layer::SubscriberExt;
+
+fn work(name: &'static str, millis: u64) {
+    let _span = info_span!("work", name).entered();
+    info_span!("load").in_scope(|| thread::sleep(Duration::from_millis(millis)));
+    info_span!("save").in_scope(|| thread::sleep(Duration::from_millis(millis / 2)));
+}
+
+fn main() -> Result<(), TBLError<String>> {
+    let layer = TimelineLayer::new();
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer.clone()))
+        .expect("a subscriber is already installed");
+
+    info_span!("main").in_scope(|| {
+        let workers: Vec<_> = [("a", 20), ("b", 40)]
+            .iter()
+            .map(|&(name, millis)| {
+                thread::Builder::new()
+                    .name(format!("worker {}", name))
+                    .spawn(move || work(name, millis))
+                    .unwrap()
+            })
+            .collect();
+        work("main", 10);
+        for worker in workers {
+            worker.join().unwrap();
+        }
+    });
+
+    // print the execution timeline on exit
+    for line in layer.render(60)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::error::Error;
+use tbl::logfile::{LogEntry, LogPatterns};
+use tbl::{Block, RenderBlock, Renderer};
+
+// a deployment log, the `web` rollout never finished
+const LOG: &str = "\
+12:00:00 INFO  rollout db started
+12:00:40 INFO  rollout cache started
+12:01:10 WARN  replica lag 2s
+12:02:00 INFO  rollout db finished
+12:02:30 INFO  rollout web started
+12:03:00 INFO  rollout cache finished
+12:03:20 ERROR health check failed
+12:04:00 INFO  drain complete
+";
+
+fn render(b: &Block<LogEntry>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, entry) => {
+            let label: String = entry
+                .as_ref()
+                .map(|e| e.label.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - label.chars().count());
+            RenderBlock::Block(label + &padding)
+        }
+    }
+}
+
+/// Seconds since midnight of a `hh:mm:ss` time.
+fn seconds(time: &str) -> Result<f64, String> {
+    let parts: Result<Vec<f64>, _> = time.split(':').map(str::parse::<f64>).collect();
+    match parts.as_deref() {
+        Ok([h, m, s]) => Ok(h * 3600. + m * 60. + s),
+        _ => Err(format!("`{}` is not a time", time)),
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let patterns = LogPatterns::new(r"^(\d\d:\d\d:\d\d)")?
+        .with_intervals(r"rollout (\w+) started", r"rollout (\w+) finished")?
+        .with_events(r"(?:WARN|ERROR)\s+(.*)")?;
+    let entries = patterns.parse(LOG, &mut seconds)?;
+    // unfinished rollouts end with the log, events are drawn 20 seconds wide
+    let last = seconds("12:04:00")?;
+    let bounds = |e: &LogEntry| match e.bounds(last) {
+        (start, end) if start == end => (start, start + 20.),
+        bounds => bounds,
+    };
+    let mut renderer = Renderer::new(entries.as_slice(), &bounds, &|e| Some(e.clone()));
+    let renderer = renderer.with_length(60).with_renderer(&render);
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -1,7 +1,6 @@
 use itertools::Itertools;
 use std::iter::FromIterator;
-use tbl::{Block, RenderBlock, Renderer, TBLError};
-use termion::color;
+use tbl::{Block, Color, RenderBlock, Renderer, Span, Style, TBLError};
 
 fn chunkify(s: &str, size: usize) -> Vec<String> {
     if size == 0 {
@@ -10,9 +9,7 @@ fn chunkify(s: &str, size: usize) -> Vec<String> {
         let inter: Vec<char> = s.chars().collect();
         let chunks = inter.chunks_exact(size);
         let remainder = chunks.remainder().to_vec();
-        let padding: Vec<char> = std::iter::repeat(' ')
-            .take(size - remainder.len())
-            .collect();
+        let padding: Vec<char> = itertools::repeat_n(' ', size - remainder.len()).collect();
         let padded_remainder: Vec<char> = remainder.iter().chain(padding.iter()).cloned().collect();
         let chunks: Vec<String> = chunks
             .chain(std::iter::once(padded_remainder.as_slice()))
@@ -24,20 +21,18 @@ fn chunkify(s: &str, size: usize) -> Vec<String> {
 
 fn render(b: &Block<String>) -> RenderBlock {
     match b {
-        Block::Space(length) => RenderBlock::Space(format!(
-            "{}{}{}",
-            color::Bg(color::Black),
+        Block::Space(length) => RenderBlock::StyledSpace(vec![vec![Span::styled(
             " ".repeat(*length),
-            color::Bg(color::Reset)
-        )),
+            Style::new().bg(Color::Black),
+        )]]),
         Block::Segment(length, label) => {
             let label = label.clone().unwrap_or_default();
             let chunks = chunkify(&label, *length);
             let color_chunks = chunks
-                .iter()
-                .map(|s| format!("{}{}{}", color::Bg(color::Blue), s, color::Bg(color::Reset)))
+                .into_iter()
+                .map(|s| vec![Span::styled(s, Style::new().bg(Color::Blue))])
                 .collect_vec();
-            RenderBlock::MultiLineBlock(color_chunks)
+            RenderBlock::StyledBlock(color_chunks)
         }
     }
 }
@@ -0,0 +1,24 @@
+use tbl::{Assignment, Palette, Renderer, TBLError};
+
+fn main() -> Result<(), TBLError<String>> {
+    let data = vec![
+        (0., 2., "build"),
+        (2., 3., "test"),
+        (4., 6., "build"),
+        (6., 7., "deploy"),
+    ];
+    let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+        Some(l.to_string())
+    });
+    let renderer = renderer.with_length(42).with_palette(
+        Palette::colorblind_safe(Assignment::FirstAppearance),
+        &|label| label.clone(),
+    );
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    for line in renderer.legend() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use tbl::{Patterns, Renderer, SegmentStyle, TBLError};
+
+fn main() -> Result<(), TBLError<String>> {
+    let data = vec![
+        (0., 2., "build"),
+        (2., 3., "test"),
+        (3., 4., "test"),
+        (4., 6., "build"),
+        (6., 7., "deploy"),
+    ];
+    let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+        Some(l.to_string())
+    });
+    let renderer = renderer
+        .with_length(42)
+        .with_patterns(Patterns::default(), &|label| label.clone())
+        .with_segment_style(SegmentStyle::Solid);
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    for line in renderer.legend() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -1,12 +1,9 @@
+use std::io;
 use tbl::{Renderer, TBLError};
 
 fn main() -> Result<(), TBLError<String>> {
     let data = vec![(0., 2.), (3., 4.)];
-    let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
         .with_length(42)
-        .render()?;
-    for line in rendered.iter().flatten() {
-        println!("{}", line);
-    }
-    Ok(())
+        .render_to(&mut io::stdout().lock())
 }
@@ -0,0 +1,34 @@
+use std::thread;
+use std::time::Duration;
+use tbl::{Recorder, TBLError};
+
+fn work(recorder: &Recorder, millis: u64) {
+    let _work = recorder.span("work");
+    {
+        let _load = recorder.span("load");
+        thread::sleep(Duration::from_millis(millis));
+    }
+    let _save = recorder.span("save");
+    thread::sleep(Duration::from_millis(millis / 2));
+}
+
+fn main() -> Result<(), TBLError<String>> {
+    let recorder = Recorder::new();
+    {
+        let _main = recorder.span("main");
+        thread::scope(|scope| {
+            for (name, millis) in [("a", 20), ("b", 40)].iter() {
+                let recorder = &recorder;
+                thread::Builder::new()
+                    .name(format!("worker {}", name))
+                    .spawn_scoped(scope, move || work(recorder, *millis))
+                    .unwrap();
+            }
+            work(&recorder, 10);
+        });
+    }
+    for line in recorder.render(60)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use chrono::{TimeZone, Utc};
+use std::error::Error;
+use tbl::timetracker::{self, TimeEntry};
+use tbl::{Block, RenderBlock, Renderer};
+
+// `timew export` of a day, the last entry is still running
+const EXPORT: &str = r#"[
+{"id": 6, "start": "20240501T070000Z", "end": "20240501T074500Z", "tags": ["admin", "mail"]},
+{"id": 5, "start": "20240501T074500Z", "end": "20240501T100000Z", "tags": ["tbl", "review"]},
+{"id": 4, "start": "20240501T100000Z", "end": "20240501T103000Z", "tags": ["admin", "standup"]},
+{"id": 3, "start": "20240501T103000Z", "end": "20240501T120000Z", "tags": ["tbl", "docs"]},
+{"id": 2, "start": "20240501T130000Z", "end": "20240501T143000Z", "tags": ["support", "tickets"]},
+{"id": 1, "start": "20240501T143000Z", "tags": ["tbl", "release"]}
+]"#;
+
+fn render(b: &Block<TimeEntry>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, entry) => {
+            let label: String = entry
+                .as_ref()
+                .map(|e| e.label.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - label.chars().count());
+            RenderBlock::Block(label + &padding)
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let at = |h| {
+        Utc.with_ymd_and_hms(2024, 5, 1, h, 0, 0)
+            .unwrap()
+            .timestamp() as f64
+    };
+    let entries = timetracker::parse_detected(EXPORT)?;
+    // the running entry ends now, say at 17:00
+    let now = at(17);
+    let mut renderer = Renderer::new(entries.as_slice(), &|e| e.bounds(now), &|e| Some(e.clone()));
+    // one swimlane per project
+    let project = |e: &TimeEntry| e.group.clone();
+    let renderer = renderer
+        .with_length(100)
+        .with_boundaries((at(7), at(18)))
+        .with_groups(&project)
+        .with_renderer(&render);
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::error::Error;
+use tbl::trace::{self, TraceEvent};
+use tbl::{Block, RenderBlock, Renderer};
+
+// a Chrome Trace Event trace, e.g. written by `chrome://tracing` or Perfetto
+const TRACE: &str = r#"{"traceEvents": [
+    {"name": "process_name", "ph": "M", "pid": 1, "args": {"name": "server"}},
+    {"name": "thread_name", "ph": "M", "pid": 1, "tid": 2, "args": {"name": "worker"}},
+    {"name": "request", "ph": "B", "ts": 0, "pid": 1, "tid": 1},
+    {"name": "parse", "ph": "X", "ts": 100, "dur": 300, "pid": 1, "tid": 1},
+    {"name": "query", "ph": "X", "ts": 500, "dur": 1200, "pid": 1, "tid": 2},
+    {"name": "cache", "ph": "X", "ts": 600, "dur": 400, "pid": 1, "tid": 2},
+    {"ph": "E", "ts": 2000, "pid": 1, "tid": 1},
+    {"name": "flush", "ph": "X", "ts": 1800, "dur": 600, "pid": 2, "tid": 1}
+]}"#;
+
+fn render(b: &Block<TraceEvent>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, event) => {
+            let name: String = event
+                .as_ref()
+                .map(|e| e.name.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - name.chars().count());
+            RenderBlock::Block(name + &padding)
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let events = trace::parse(TRACE)?;
+    let mut renderer = Renderer::new(events.as_slice(), &|e| e.bounds, &|e| Some(e.clone()));
+    // one swimlane per thread
+    let group = |e: &TraceEvent| Some(e.group());
+    let renderer = renderer
+        .with_length(60)
+        .with_groups(&group)
+        .with_renderer(&render);
+    for line in renderer.render()?.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use tbl::{Orientation, Renderer, SegmentStyle, TBLError};
+
+fn main() -> Result<(), TBLError<String>> {
+    // (start, end) in minutes since 08:00, one row per 15 minutes
+    let data = vec![
+        (0., 30., "standup"),
+        (30., 120., "review"),
+        (60., 90., "1:1"),
+        (150., 210., "lunch"),
+        (210., 300., "planning"),
+        (240., 270., "interview"),
+    ];
+    let rendered = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+        Some(l.to_string())
+    })
+    .with_length(20)
+    .with_boundaries((0., 300.))
+    .with_orientation(Orientation::Vertical)
+    .with_segment_style(SegmentStyle::Box)
+    .with_label_format(&|label| label.clone())
+    .render()?;
+    for line in rendered.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use tbl::{Renderer, SegmentStyle, TBLError, Wrap};
+
+const HOUR: f64 = 3600.;
+const DAY: f64 = 24. * HOUR;
+
+fn main() -> Result<(), TBLError<String>> {
+    // (start, end) in seconds since Monday 00:00, one row per day
+    let at = |day: f64, hour: f64| day * DAY + hour * HOUR;
+    let data = vec![
+        (at(0., 9.), at(0., 12.5), "backup"),
+        (at(0., 22.), at(1., 6.), "batch"),
+        (at(1., 10.), at(1., 11.), "deploy"),
+        (at(2., 8.), at(2., 18.), "migration"),
+        (at(2., 23.), at(4., 2.), "reindex"),
+        (at(4., 14.), at(4., 15.), "deploy"),
+    ];
+    let days = ["Mon", "Tue", "Wed", "Thu", "Fri"];
+    let header = |(start, _): (f64, f64)| {
+        let day = (start / DAY) as usize;
+        days.get(day).map(|d| d.to_string()).unwrap_or_default()
+    };
+    let rendered = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+        Some(l.to_string())
+    })
+    .with_length(48)
+    .with_segment_style(SegmentStyle::Ascii)
+    .with_wrap(Wrap::Span(DAY), &header)
+    .render()?;
+    for line in rendered.iter().flatten() {
+        println!("{}", line);
+    }
+    Ok(())
+}
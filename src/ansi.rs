@@ -0,0 +1,310 @@
+//! ANSI escape codes backend.
+
+use crate::style::{Color, Span, Style};
+use std::env;
+
+const ESC: &str = "\x1b[";
+const RESET: &str = "\x1b[0m";
+
+/// Colors supported by the output terminal.
+///
+/// Colors are downgraded to the nearest supported color when rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorLevel {
+    /// No colors, only text attributes (bold, italic...) are rendered.
+    Monochrome,
+    /// The 16 standard colors.
+    Ansi16,
+    /// The 256 colors palette.
+    Ansi256,
+    /// 24 bits colors.
+    TrueColor,
+}
+
+/// The 16 standard colors (xterm defaults).
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+const CUBE_STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorLevel {
+    /// Detect terminal color support from the environment.
+    ///
+    /// see `ColorLevel::from_env`
+    pub fn detect() -> Self {
+        ColorLevel::from_env(
+            env::var("NO_COLOR").ok().as_deref(),
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Color support given `NO_COLOR`, `COLORTERM` and `TERM` values.
+    ///
+    /// ```
+    /// use tbl::ColorLevel;
+    /// assert_eq!(ColorLevel::from_env(None, Some("truecolor"), Some("xterm")), ColorLevel::TrueColor);
+    /// assert_eq!(ColorLevel::from_env(None, None, Some("screen-256color")), ColorLevel::Ansi256);
+    /// assert_eq!(ColorLevel::from_env(None, None, Some("xterm")), ColorLevel::Ansi16);
+    /// assert_eq!(ColorLevel::from_env(None, None, Some("dumb")), ColorLevel::Monochrome);
+    /// assert_eq!(ColorLevel::from_env(Some("1"), Some("truecolor"), None), ColorLevel::Monochrome);
+    /// ```
+    pub fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color.is_some_and(|v| !v.is_empty()) {
+            return ColorLevel::Monochrome;
+        }
+        if let Some("truecolor") | Some("24bit") = colorterm {
+            return ColorLevel::TrueColor;
+        }
+        match term {
+            None | Some("") | Some("dumb") => ColorLevel::Monochrome,
+            Some(term) if term.contains("truecolor") || term.contains("direct") => {
+                ColorLevel::TrueColor
+            }
+            Some(term) if term.contains("256") => ColorLevel::Ansi256,
+            Some(_) => ColorLevel::Ansi16,
+        }
+    }
+
+    /// Nearest color supported at this level, `None` if colors are not supported.
+    ///
+    /// ```
+    /// use tbl::{Color, ColorLevel};
+    /// assert_eq!(ColorLevel::Ansi256.downgrade(Color::Rgb(255, 152, 0)), Some(Color::Indexed(208)));
+    /// assert_eq!(ColorLevel::Ansi16.downgrade(Color::Rgb(3, 169, 244)), Some(Color::Cyan));
+    /// assert_eq!(ColorLevel::Monochrome.downgrade(Color::Red), None);
+    /// ```
+    pub fn downgrade(&self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (ColorLevel::Monochrome, _) => None,
+            (ColorLevel::TrueColor, color) => Some(color),
+            (ColorLevel::Ansi256, Color::Rgb(r, g, b)) => {
+                Some(Color::Indexed(nearest_256(r, g, b)))
+            }
+            (ColorLevel::Ansi256, color) => Some(color),
+            (ColorLevel::Ansi16, Color::Rgb(r, g, b)) => Some(nearest_16(r, g, b)),
+            (ColorLevel::Ansi16, Color::Indexed(i)) => {
+                let (r, g, b) = indexed_rgb(i);
+                Some(nearest_16(r, g, b))
+            }
+            (ColorLevel::Ansi16, color) => Some(color),
+        }
+    }
+
+    /// Render a line as a string with ANSI escape codes.
+    ///
+    /// Unstyled spans are written as is.
+    ///
+    /// ```
+    /// use tbl::{Color, ColorLevel, Span, Style};
+    /// let line = vec![Span::styled("ab", Style::new().bg(Color::Rgb(255, 0, 0))), Span::raw("c")];
+    /// assert_eq!(ColorLevel::TrueColor.paint(&line), "\u{1b}[48;2;255;0;0mab\u{1b}[0mc");
+    /// assert_eq!(ColorLevel::Ansi16.paint(&line), "\u{1b}[101mab\u{1b}[0mc");
+    /// assert_eq!(ColorLevel::Monochrome.paint(&line), "abc");
+    /// ```
+    pub fn paint(&self, line: &[Span]) -> String {
+        line.iter()
+            .map(|span| {
+                let style = Style {
+                    fg: span.style.fg.and_then(|c| self.downgrade(c)),
+                    bg: span.style.bg.and_then(|c| self.downgrade(c)),
+                    ..span.style
+                };
+                if style.is_plain() || span.text.is_empty() {
+                    span.text.clone()
+                } else {
+                    format!("{}{}{}", sgr(&style), span.text, RESET)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Remove ANSI escape sequences (e.g. colors embedded by a block renderer).
+pub(crate) fn strip(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters then a final byte in `@`..=`~`
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ST
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.peek() == Some(&'\\')) {
+                        if c == '\x1b' {
+                            chars.next();
+                        }
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    stripped
+}
+
+fn distance(left: (u8, u8, u8), right: (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    d(left.0, right.0) + d(left.1, right.1) + d(left.2, right.2)
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::White)
+}
+
+fn nearest_cube_step(v: u8) -> usize {
+    (0..CUBE_STEPS.len())
+        .min_by_key(|&i| (i32::from(CUBE_STEPS[i]) - i32::from(v)).abs())
+        .unwrap_or_default()
+}
+
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (
+        nearest_cube_step(r),
+        nearest_cube_step(g),
+        nearest_cube_step(b),
+    );
+    let cube_index = (16 + 36 * ri + 6 * gi + bi) as u8;
+    let cube_rgb = (CUBE_STEPS[ri], CUBE_STEPS[gi], CUBE_STEPS[bi]);
+    let average = ((u16::from(r) + u16::from(g) + u16::from(b)) / 3) as u8;
+    let gray_step = (usize::from(average.saturating_sub(3)) / 10).min(23);
+    let gray_index = (232 + gray_step) as u8;
+    let gray_rgb = indexed_rgb(gray_index);
+    if distance(gray_rgb, (r, g, b)) < distance(cube_rgb, (r, g, b)) {
+        gray_index
+    } else {
+        cube_index
+    }
+}
+
+pub(crate) fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) => indexed_rgb(i),
+        named => ANSI16
+            .iter()
+            .find(|(c, _)| *c == named)
+            .map(|(_, rgb)| *rgb)
+            .unwrap_or_default(),
+    }
+}
+
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI16[usize::from(i)].1,
+        16..=231 => {
+            let i = usize::from(i - 16);
+            (
+                CUBE_STEPS[i / 36],
+                CUBE_STEPS[(i / 6) % 6],
+                CUBE_STEPS[i % 6],
+            )
+        }
+        _ => {
+            let v = 8 + 10 * (i - 232);
+            (v, v, v)
+        }
+    }
+}
+
+fn color_code(color: Color, background: bool) -> String {
+    let offset = if background { 10 } else { 0 };
+    match color {
+        Color::Black => format!("{}", 30 + offset),
+        Color::Red => format!("{}", 31 + offset),
+        Color::Green => format!("{}", 32 + offset),
+        Color::Yellow => format!("{}", 33 + offset),
+        Color::Blue => format!("{}", 34 + offset),
+        Color::Magenta => format!("{}", 35 + offset),
+        Color::Cyan => format!("{}", 36 + offset),
+        Color::White => format!("{}", 37 + offset),
+        Color::BrightBlack => format!("{}", 90 + offset),
+        Color::BrightRed => format!("{}", 91 + offset),
+        Color::BrightGreen => format!("{}", 92 + offset),
+        Color::BrightYellow => format!("{}", 93 + offset),
+        Color::BrightBlue => format!("{}", 94 + offset),
+        Color::BrightMagenta => format!("{}", 95 + offset),
+        Color::BrightCyan => format!("{}", 96 + offset),
+        Color::BrightWhite => format!("{}", 97 + offset),
+        Color::Indexed(i) => format!("{};5;{}", 38 + offset, i),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", 38 + offset, r, g, b),
+    }
+}
+
+fn sgr(style: &Style) -> String {
+    let mut codes: Vec<String> = vec![];
+    if style.bold {
+        codes.push("1".to_string());
+    }
+    if style.dim {
+        codes.push("2".to_string());
+    }
+    if style.italic {
+        codes.push("3".to_string());
+    }
+    if style.underline {
+        codes.push("4".to_string());
+    }
+    if let Some(fg) = style.fg {
+        codes.push(color_code(fg, false));
+    }
+    if let Some(bg) = style.bg {
+        codes.push(color_code(bg, true));
+    }
+    format!("{}{}m", ESC, codes.join(";"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ansi::{indexed_rgb, nearest_256, strip};
+
+    #[test]
+    fn test_nearest_256() {
+        for i in 16..=255u8 {
+            let (r, g, b) = indexed_rgb(i);
+            assert_eq!(indexed_rgb(nearest_256(r, g, b)), (r, g, b));
+        }
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_256(128, 128, 128), 244);
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip("\x1b[48;2;3;169;244mab\x1b[0m c"), "ab c");
+        assert_eq!(strip("\x1b]8;;http://a\x1b\\link\x1b]8;;\x07"), "link");
+        assert_eq!(strip("plain"), "plain");
+    }
+}
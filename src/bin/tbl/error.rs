@@ -0,0 +1,42 @@
+//! Errors of the `tbl` binary.
+
+use std::io;
+use tbl::gitlog::GitLogError;
+use tbl::ics::IcsError;
+use tbl::logfile::LogError;
+use tbl::timetracker::TrackerError;
+use tbl::trace::TraceError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum CliError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    Csv(#[from] csv::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Trace(#[from] TraceError),
+    #[error("{0}")]
+    Ics(#[from] IcsError),
+    #[error("{0}")]
+    Tracker(#[from] TrackerError),
+    #[error("{0}")]
+    Log(#[from] LogError),
+    #[error("{0}")]
+    GitLog(#[from] GitLogError),
+    #[error("line {line}: {message}")]
+    Parse { line: u64, message: String },
+    /// JSON Lines line or JSON array element.
+    #[error("record {index}: {message}")]
+    Record { index: usize, message: String },
+    #[error("--{0}: {1}")]
+    Argument(&'static str, String),
+    #[error("unknown column `{0}`")]
+    Column(String),
+    #[error("`{0}` overlaps `{1}`")]
+    Overlap(String, String),
+    #[error("{0}")]
+    Render(String),
+}
@@ -0,0 +1,116 @@
+//! `git log` input: commit sessions and commits grouped by author.
+
+use crate::error::CliError;
+use crate::input::{Label, Row};
+use std::collections::HashMap;
+use tbl::gitlog;
+
+/// Rows of the commit sessions (consecutive commits closer than `gap` seconds) and of the
+/// commits (empty rows labelled by subject) of a `git log --format` output, grouped by author.
+pub(crate) fn parse_git_log(text: &str, gap: f64) -> Result<(Vec<Row>, Vec<Row>), CliError> {
+    let commits = gitlog::parse(text)?;
+    let sessions = gitlog::sessions(&commits, gap)
+        .into_iter()
+        .map(|session| Row {
+            start: session.bounds.0,
+            end: session.bounds.1,
+            label: Label {
+                text: match session.commits {
+                    1 => "1 commit".to_string(),
+                    commits => format!("{} commits", commits),
+                },
+                group: Some(session.author),
+            },
+        });
+    let commits = commits.into_iter().map(|commit| Row {
+        start: commit.time,
+        end: commit.time,
+        label: Label {
+            text: commit.subject,
+            group: Some(commit.author),
+        },
+    });
+    Ok((sessions.collect(), commits.collect()))
+}
+
+/// Sessions and their commits (one character wide markers) on the same lane: sessions are cut
+/// around the commits of their author, overlapping commits are merged.
+pub(crate) fn place_commits(sessions: Vec<Row>, mut commits: Vec<Row>) -> Vec<Row> {
+    commits.sort_by(|a, b| a.start.total_cmp(&b.start));
+    // merged commits by author, sorted by start
+    let mut authors: HashMap<Option<String>, Vec<Row>> = HashMap::new();
+    for commit in commits {
+        let merged = authors.entry(commit.label.group.clone()).or_default();
+        match merged.last_mut() {
+            Some(last) if commit.start < last.end => {
+                last.end = last.end.max(commit.end);
+                last.label.text = format!("{}, {}", last.label.text, commit.label.text);
+            }
+            _ => merged.push(commit),
+        }
+    }
+    let mut rows = vec![];
+    for session in sessions {
+        let commits = authors
+            .get(&session.label.group)
+            .map_or(&[][..], Vec::as_slice);
+        let first = commits.partition_point(|commit| commit.end <= session.start);
+        let mut start = session.start;
+        for commit in commits[first..]
+            .iter()
+            .take_while(|commit| commit.start < session.end)
+        {
+            if start < commit.start {
+                rows.push(Row {
+                    start,
+                    end: commit.start,
+                    label: session.label.clone(),
+                });
+            }
+            start = start.max(commit.end);
+        }
+        if start < session.end {
+            rows.push(Row { start, ..session });
+        }
+    }
+    let mut commits: Vec<Row> = authors.into_values().flatten().collect();
+    commits.sort_by(|a, b| a.start.total_cmp(&b.start));
+    rows.extend(commits);
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gitlog::{parse_git_log, place_commits};
+    use crate::input::Row;
+    use crate::overlap::{apply, Overlap};
+
+    fn bounds(rows: &[Row]) -> Vec<(f64, f64, &str)> {
+        rows.iter()
+            .map(|row| (row.start, row.end, row.label.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_place_commits() {
+        let text = "0\tAda\ta\n100\tAda\tb\n100\tAda\tc\n50\tGrace\td\n";
+        let (sessions, commits) = parse_git_log(text, 3600.).unwrap();
+        let widen = |mut commit: Row| {
+            commit.end += 10.;
+            commit
+        };
+        let rows = place_commits(sessions, commits.into_iter().map(widen).collect());
+        // simultaneous commits are merged, Grace's single commit session is its commit
+        assert_eq!(
+            bounds(&rows),
+            vec![
+                (10., 100., "3 commits"),
+                (0., 10., "a"),
+                (50., 60., "d"),
+                (100., 110., "b, c"),
+            ]
+        );
+        // commits do not overlap their session
+        assert!(apply(rows, Overlap::Error).is_ok());
+    }
+}
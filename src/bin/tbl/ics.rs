@@ -0,0 +1,84 @@
+//! iCalendar (`.ics`) input: one interval per event occurrence.
+
+use crate::error::CliError;
+use crate::input::{Label, Row};
+use std::path::Path;
+use tbl::{ics, Bound};
+
+/// Does `text` look like an iCalendar calendar?
+pub(crate) fn is_ics(text: &str) -> bool {
+    text.trim_start().starts_with("BEGIN:VCALENDAR")
+}
+
+/// Rows of the events of a calendar intersecting `window`, labelled by summary and grouped by
+/// attendee or by calendar (its name, or the stem of `file`).
+pub(crate) fn parse_ics(
+    text: &str,
+    file: &str,
+    window: Option<Bound>,
+    by_attendee: bool,
+) -> Result<Vec<Row>, CliError> {
+    let mut events = ics::parse(text, window)?;
+    if by_attendee {
+        events = ics::by_attendee(events);
+    }
+    let stem = Path::new(file)
+        .file_stem()
+        .filter(|_| file != "-")
+        .map(|stem| stem.to_string_lossy().to_string());
+    Ok(events
+        .into_iter()
+        .map(|event| {
+            let group = if by_attendee {
+                event.attendees.first().cloned()
+            } else {
+                event.calendar.or_else(|| stem.clone())
+            };
+            Row {
+                start: event.bounds.0,
+                end: event.bounds.1,
+                label: Label {
+                    text: event.summary,
+                    group,
+                },
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ics::{is_ics, parse_ics};
+
+    #[test]
+    fn test_groups() {
+        let event = "BEGIN:VEVENT\nDTSTART:20240501T100000Z\n\
+                     ATTENDEE;CN=Alice:mailto:a@example.com\nEND:VEVENT\n\
+                     BEGIN:VEVENT\nDTSTART:20240501T110000Z\nEND:VEVENT\n";
+        let text = format!("BEGIN:VCALENDAR\n{}END:VCALENDAR\n", event);
+        assert!(is_ics(&format!("\n  {}", text)));
+        let group = |text: &str, file: &str, by_attendee: bool| {
+            parse_ics(text, file, None, by_attendee)
+                .unwrap()
+                .into_iter()
+                .map(|row| row.label.group)
+                .collect::<Vec<_>>()
+        };
+        let team = Some("team".to_string());
+        assert_eq!(
+            group(&text, "calendars/team.ics", false),
+            [team.clone(), team]
+        );
+        // no stem for the standard input
+        assert_eq!(group(&text, "-", false), [None, None]);
+        // the calendar name before the stem
+        let named = format!(
+            "BEGIN:VCALENDAR\nX-WR-CALNAME:Ops\n{}END:VCALENDAR\n",
+            event
+        );
+        assert_eq!(group(&named, "team.ics", false)[0].as_deref(), Some("Ops"));
+        // events without attendees are not grouped
+        let alice = Some("Alice".to_string());
+        assert_eq!(group(&named, "team.ics", true), [alice, None]);
+    }
+}
@@ -0,0 +1,250 @@
+//! Delimited (CSV, TSV...) input: one interval per row.
+
+use crate::error::CliError;
+use crate::time::{TimeError, TimeParser};
+use csv::{ReaderBuilder, StringRecord, Trim};
+
+/// Delimiters tried by `detect_delimiter`, in order of preference.
+const DELIMITERS: [u8; 4] = [b'\t', b',', b';', b'|'];
+
+/// Label of an interval: its text and its group (swimlane).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Label {
+    pub(crate) text: String,
+    pub(crate) group: Option<String>,
+}
+
+/// An interval read from the input.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Row {
+    pub(crate) start: f64,
+    pub(crate) end: f64,
+    pub(crate) label: Label,
+}
+
+/// A column selected by (1-based) index or by header name.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    pub(crate) fn parse(column: &str) -> Column {
+        match column.parse::<usize>() {
+            Ok(index) if index > 0 => Column::Index(index - 1),
+            _ => Column::Name(column.to_string()),
+        }
+    }
+
+    fn resolve(&self, header: Option<&StringRecord>) -> Result<usize, CliError> {
+        match (self, header) {
+            (Column::Index(index), _) => Ok(*index),
+            (Column::Name(name), Some(header)) => header
+                .iter()
+                .position(|field| field == name)
+                .ok_or_else(|| CliError::Column(name.clone())),
+            (Column::Name(name), None) => Err(CliError::Column(name.clone())),
+        }
+    }
+}
+
+/// Columns of `start`, `end`, `label` and `group`, label and group are optional.
+#[derive(Debug, Clone)]
+pub(crate) struct Columns {
+    pub(crate) start: Column,
+    pub(crate) end: Column,
+    pub(crate) label: Column,
+    pub(crate) group: Column,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns {
+            start: Column::Index(0),
+            end: Column::Index(1),
+            label: Column::Index(2),
+            group: Column::Index(3),
+        }
+    }
+}
+
+impl Columns {
+    fn by_name(&self) -> bool {
+        [&self.start, &self.end, &self.label, &self.group]
+            .iter()
+            .any(|column| matches!(column, Column::Name(_)))
+    }
+}
+
+/// The most frequent delimiter of the first line, `,` by default.
+pub(crate) fn detect_delimiter(text: &str) -> u8 {
+    let first = text
+        .lines()
+        .find(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .unwrap_or_default();
+    DELIMITERS
+        .iter()
+        .map(|&d| (d, first.bytes().filter(|&b| b == d).count()))
+        .filter(|&(_, count)| count > 0)
+        .fold(None, |best: Option<(u8, usize)>, (d, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((d, count)),
+        })
+        .map_or(b',', |(d, _)| d)
+}
+
+fn bounds(
+    record: &StringRecord,
+    (start, end): (usize, usize),
+    time: &mut TimeParser,
+    line: u64,
+) -> Result<(f64, f64), CliError> {
+    let error = |e: TimeError| CliError::Parse {
+        line,
+        message: e.to_string(),
+    };
+    let start = time
+        .bound(record.get(start).unwrap_or_default())
+        .map_err(error)?;
+    let end = time
+        .end(record.get(end).unwrap_or_default(), start)
+        .map_err(error)?;
+    Ok((start, end))
+}
+
+fn field(record: &StringRecord, index: usize) -> Option<String> {
+    record
+        .get(index)
+        .filter(|field| !field.is_empty())
+        .map(str::to_string)
+}
+
+/// Parse delimited rows, the first row is a header if `header` or if columns are selected by name.
+///
+/// Empty lines and lines starting with `#` are skipped, bounds are parsed by `time`.
+pub(crate) fn parse_delimited(
+    text: &str,
+    delimiter: Option<u8>,
+    columns: &Columns,
+    header: bool,
+    time: &mut TimeParser,
+) -> Result<Vec<Row>, CliError> {
+    let header = header || columns.by_name();
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter.unwrap_or_else(|| detect_delimiter(text)))
+        .has_headers(header)
+        .flexible(true)
+        .trim(Trim::All)
+        .comment(Some(b'#'))
+        .from_reader(text.as_bytes());
+    let names = if header {
+        Some(reader.headers()?.clone())
+    } else {
+        None
+    };
+    let start = columns.start.resolve(names.as_ref())?;
+    let end = columns.end.resolve(names.as_ref())?;
+    let label = columns.label.resolve(names.as_ref())?;
+    let group = columns.group.resolve(names.as_ref())?;
+    reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let line = record.position().map_or(0, |p| p.line());
+            let (start, end) = bounds(&record, (start, end), time, line)?;
+            Ok(Row {
+                start,
+                end,
+                label: Label {
+                    text: field(&record, label).unwrap_or_default(),
+                    group: field(&record, group),
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::{detect_delimiter, parse_delimited, Column, Columns, Label, Row};
+    use crate::time::TimeParser;
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("1,2,a b"), b',');
+        assert_eq!(detect_delimiter("# comment, here\n1\t2\ta,b"), b'\t');
+        assert_eq!(detect_delimiter("1;2;a,b"), b';');
+        assert_eq!(detect_delimiter("1"), b',');
+    }
+
+    #[test]
+    fn test_parse_delimited() {
+        let rows = parse_delimited(
+            "1,2,a\n\n3, 4 ,b,g\n",
+            None,
+            &Columns::default(),
+            false,
+            &mut TimeParser::new(None, None),
+        );
+        assert_eq!(
+            rows.unwrap(),
+            vec![
+                Row {
+                    start: 1.,
+                    end: 2.,
+                    label: Label {
+                        text: "a".to_string(),
+                        group: None
+                    }
+                },
+                Row {
+                    start: 3.,
+                    end: 4.,
+                    label: Label {
+                        text: "b".to_string(),
+                        group: Some("g".to_string())
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_times() {
+        let text =
+            "2024-05-01T10:00:00Z,+30m,a\n2024-05-01T11:00:00Z,2024-05-01T12:00:00Z,b\n10:00,+1h,c";
+        let mut time = TimeParser::new(None, None);
+        let error = parse_delimited(text, None, &Columns::default(), false, &mut time);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "line 3: `10:00` is a time of day, previous bounds are a date"
+        );
+    }
+
+    #[test]
+    fn test_parse_by_name() {
+        let columns = Columns {
+            start: Column::parse("from"),
+            end: Column::parse("to"),
+            label: Column::parse("1"),
+            group: Column::parse("9"),
+        };
+        let mut time = TimeParser::new(None, None);
+        let rows = parse_delimited(
+            "name\tfrom\tto\nx\t0\t1\n",
+            None,
+            &columns,
+            false,
+            &mut time,
+        );
+        let rows = rows.unwrap();
+        assert_eq!((rows[0].start, rows[0].end), (0., 1.));
+        assert_eq!(rows[0].label.text, "x");
+        let error = parse_delimited("0,one", None, &Columns::default(), false, &mut time);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "line 1: `one` is not a time"
+        );
+    }
+}
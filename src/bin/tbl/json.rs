@@ -0,0 +1,316 @@
+//! JSON input: an array of records or one record per line (JSON Lines).
+
+use crate::error::CliError;
+use crate::input::{Column, Columns, Label, Row};
+use crate::time::{TimeError, TimeParser};
+use serde_json::Value;
+use tbl::trace;
+
+/// A step of a `Selector`.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// JSONPath-like field selector, e.g. `$.span.start`, `attributes.name` or `[0]`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Selector {
+    steps: Vec<Step>,
+}
+
+impl Selector {
+    pub(crate) fn parse(selector: &str) -> Selector {
+        let selector = selector.strip_prefix('$').unwrap_or(selector);
+        let steps = selector
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .flat_map(|part| {
+                // `key[0][1]`
+                let mut parts = part.split('[');
+                let key = parts
+                    .next()
+                    .filter(|key| !key.is_empty())
+                    .map(|key| Step::Key(key.to_string()));
+                let indices = parts.map(|index| {
+                    let index = index.trim_end_matches(']');
+                    match index.parse() {
+                        Ok(index) => Step::Index(index),
+                        Err(_) => {
+                            Step::Key(index.trim_matches(|c| c == '"' || c == '\'').to_string())
+                        }
+                    }
+                });
+                key.into_iter().chain(indices).collect::<Vec<Step>>()
+            })
+            .collect();
+        Selector { steps }
+    }
+
+    /// `Column::Index` selects an element of an array record (from 1).
+    fn from_column(column: &Column) -> Selector {
+        match column {
+            Column::Index(index) => Selector {
+                steps: vec![Step::Index(*index)],
+            },
+            Column::Name(name) => Selector::parse(name),
+        }
+    }
+
+    fn select<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.steps
+            .iter()
+            .try_fold(value, |value, step| match step {
+                Step::Key(key) => value.get(key),
+                Step::Index(index) => value.get(index),
+            })
+            .filter(|value| !value.is_null())
+    }
+}
+
+/// Selectors of `start`, `end`, `label` and `group`.
+pub(crate) struct Selectors {
+    start: Selector,
+    end: Selector,
+    label: Selector,
+    group: Selector,
+}
+
+impl Selectors {
+    pub(crate) fn new(columns: &Columns) -> Selectors {
+        Selectors {
+            start: Selector::from_column(&columns.start),
+            end: Selector::from_column(&columns.end),
+            label: Selector::from_column(&columns.label),
+            group: Selector::from_column(&columns.group),
+        }
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `None` if `start` or `end` is missing or invalid, bounds of another kind are an error.
+fn row(
+    record: &Value,
+    selectors: &Selectors,
+    time: &mut TimeParser,
+) -> Result<Option<Row>, TimeError> {
+    let (start, end) = match (selectors.start.select(record), selectors.end.select(record)) {
+        (Some(start), Some(end)) => (text(start), text(end)),
+        _ => return Ok(None),
+    };
+    let bounds = time
+        .bound(&start)
+        .and_then(|start| time.end(&end, start).map(|end| (start, end)));
+    match bounds {
+        Ok((start, end)) => Ok(Some(Row {
+            start,
+            end,
+            label: Label {
+                text: selectors.label.select(record).map(text).unwrap_or_default(),
+                group: selectors.group.select(record).map(text),
+            },
+        })),
+        Err(TimeError::Invalid(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Does `text` look like JSON (an array or an object)?
+pub(crate) fn is_json(text: &str) -> bool {
+    matches!(text.trim_start().chars().next(), Some('[') | Some('{'))
+}
+
+/// Does `text` look like a Chrome Trace Event trace?
+pub(crate) fn is_trace(text: &str) -> bool {
+    let text = text.trim_start();
+    (text.starts_with('{') && text.contains("\"traceEvents\""))
+        || (text.starts_with('[') && text.contains("\"ph\"") && text.contains("\"ts\""))
+}
+
+/// Rows of a Chrome Trace Event trace: event names grouped by `process/thread`, in microseconds.
+pub(crate) fn parse_trace(text: &str) -> Result<Vec<Row>, CliError> {
+    Ok(trace::parse(text)?
+        .into_iter()
+        .map(|event| Row {
+            start: event.bounds.0,
+            end: event.bounds.1,
+            label: Label {
+                group: Some(event.group()),
+                text: event.name,
+            },
+        })
+        .collect())
+}
+
+/// Elements of a JSON array, split at the commas outside of nested values and strings, `None` if
+/// `text` is not enclosed in brackets.
+fn elements(text: &str) -> Option<Vec<&str>> {
+    let body = text.trim().strip_prefix('[')?.strip_suffix(']')?;
+    if body.trim().is_empty() {
+        return Some(vec![]);
+    }
+    let mut elements = vec![];
+    let (mut depth, mut start) = (0usize, 0);
+    let (mut string, mut escaped) = (false, false);
+    for (i, c) in body.char_indices() {
+        if string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => string = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                elements.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    elements.push(body[start..].trim());
+    Some(elements)
+}
+
+/// Rows and the number of skipped (malformed) records.
+///
+/// `text` is either a JSON array of records or JSON Lines (one record per line),
+/// bounds are parsed by `time`. Malformed array elements are skipped like malformed lines.
+pub(crate) fn parse_json(
+    text: &str,
+    selectors: &Selectors,
+    time: &mut TimeParser,
+) -> Result<(Vec<Row>, usize), CliError> {
+    // (line or record number, record)
+    let records: Vec<(usize, Option<Value>)> = if text.trim_start().starts_with('[') {
+        let records: Vec<Option<Value>> = match serde_json::from_str::<Vec<Value>>(text) {
+            Ok(records) => records.into_iter().map(Some).collect(),
+            // malformed elements are skipped
+            Err(e) => elements(text)
+                .ok_or(e)?
+                .into_iter()
+                .map(|element| serde_json::from_str(element).ok())
+                .collect(),
+        };
+        records
+            .into_iter()
+            .enumerate()
+            .map(|(i, record)| (i + 1, record))
+            .collect()
+    } else {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i + 1, serde_json::from_str(line).ok()))
+            .collect()
+    };
+    let mut rows = vec![];
+    for (index, record) in records.iter() {
+        if let Some(record) = record {
+            match row(record, selectors, time) {
+                Ok(Some(row)) => rows.push(row),
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(CliError::Record {
+                        index: *index,
+                        message: e.to_string(),
+                    })
+                }
+            }
+        }
+    }
+    let skipped = records.len() - rows.len();
+    Ok((rows, skipped))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::{Column, Columns};
+    use crate::json::{is_trace, parse_json, parse_trace, Selector, Selectors, Step};
+    use crate::time::TimeParser;
+
+    fn selectors(start: &str, end: &str, label: &str, group: &str) -> Selectors {
+        Selectors::new(&Columns {
+            start: Column::parse(start),
+            end: Column::parse(end),
+            label: Column::parse(label),
+            group: Column::parse(group),
+        })
+    }
+
+    #[test]
+    fn test_selector() {
+        assert_eq!(
+            Selector::parse("$.span.tags[1]").steps,
+            vec![
+                Step::Key("span".to_string()),
+                Step::Key("tags".to_string()),
+                Step::Index(1)
+            ]
+        );
+        assert_eq!(
+            Selector::parse("$['key'][0]").steps,
+            vec![Step::Key("key".to_string()), Step::Index(0)]
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let text = r#"{"span": {"start": 0, "end": "2.5"}, "name": "a"}
+            not json
+            {"span": {"start": 1}, "name": "no end"}
+
+            {"span": {"start": 3, "end": 4}, "name": {"id": 1}, "svc": "api"}"#;
+        let selectors = selectors("$.span.start", "span.end", "name", "svc");
+        let (rows, skipped) =
+            parse_json(text, &selectors, &mut TimeParser::new(None, None)).unwrap();
+        assert_eq!(skipped, 2);
+        assert_eq!((rows[0].start, rows[0].end), (0., 2.5));
+        assert_eq!(rows[1].label.text, r#"{"id":1}"#);
+        assert_eq!(rows[1].label.group.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn test_json_array() {
+        let selectors = selectors("1", "2", "3", "4");
+        let mut time = TimeParser::new(None, None);
+        let (rows, skipped) =
+            parse_json(r#"[[0, 1, "a"], [1, null], [2, 3]]"#, &selectors, &mut time).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].label.text, "a");
+        assert!(parse_json("[0, 1", &selectors, &mut time).is_err());
+        // malformed elements, strings and nested values are not split
+        let text = r#"[[0, 1, "a, [b"], [1, 2, ], {"x": [3, 4]}, [2, 3, "c]\""]]"#;
+        let (rows, skipped) = parse_json(text, &selectors, &mut time).unwrap();
+        assert_eq!(skipped, 2);
+        assert_eq!(rows[0].label.text, "a, [b");
+        assert_eq!(rows[1].label.text, "c]\"");
+        let error = parse_json(r#"[[0, "+1h"], ["10:00", 1]]"#, &selectors, &mut time);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "record 2: `10:00` is a time of day, previous bounds are a number"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let text = r#"[{"name": "a", "ph": "X", "ts": 1.5, "dur": 0.5, "pid": 1, "tid": 2}]"#;
+        assert!(is_trace(text));
+        assert!(!is_trace(r#"[{"start": 0, "end": 1}]"#));
+        let rows = parse_trace(text).unwrap();
+        assert_eq!((rows[0].start, rows[0].end), (1.5, 2.));
+        assert_eq!(rows[0].label.group.as_deref(), Some("1/2"));
+    }
+}
@@ -0,0 +1,59 @@
+//! Log input: intervals and events extracted from lines by regular expressions.
+
+use crate::error::CliError;
+use crate::input::{Label, Row};
+use crate::time::TimeParser;
+use tbl::logfile::LogPatterns;
+
+/// Rows of the intervals and of the point events (empty rows) of a log labelled by key or
+/// event text, unfinished intervals end with the last interval or event.
+pub(crate) fn parse_log(
+    text: &str,
+    patterns: &LogPatterns,
+    time: &mut TimeParser,
+) -> Result<(Vec<Row>, Vec<Row>), CliError> {
+    let entries = patterns.parse(text, &mut |t| time.bound(t).map_err(|e| e.to_string()))?;
+    let last = entries
+        .iter()
+        .map(|entry| entry.end.unwrap_or(entry.start))
+        .fold(f64::NEG_INFINITY, f64::max);
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let (start, end) = entry.bounds(last);
+            Row {
+                start,
+                end,
+                label: Label {
+                    text: entry.label,
+                    group: None,
+                },
+            }
+        })
+        .partition(|row| row.start != row.end))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::logfile::parse_log;
+    use crate::time::TimeParser;
+    use tbl::logfile::LogPatterns;
+
+    #[test]
+    fn test_events() {
+        let text = "10:00 start a\n10:01 WARN disk\n10:02 end a\n10:02 start b\n10:05 WARN disk\n";
+        let patterns = LogPatterns::new(r"^(\S+)")
+            .unwrap()
+            .with_intervals(r"start (\w+)", r"end (\w+)")
+            .unwrap()
+            .with_events(r"WARN (\w+)")
+            .unwrap();
+        let (intervals, events) =
+            parse_log(text, &patterns, &mut TimeParser::new(None, None)).unwrap();
+        assert_eq!(intervals.len(), 2);
+        // b is not finished, it ends with the last event
+        assert_eq!(intervals[1].end - intervals[1].start, 180.);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.start == event.end));
+    }
+}
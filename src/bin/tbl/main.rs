@@ -0,0 +1,604 @@
+//! `tbl`: render intervals read from delimited files (or stdin) as a timeline.
+//!
+//! ```text
+//! $ printf '0,2,build\n1,3,test\n' | tbl --width 20 --labels
+//!       test==========
+//! build========
+//! ```
+
+mod error;
+mod gitlog;
+mod ics;
+mod input;
+mod json;
+mod logfile;
+mod overlap;
+mod time;
+mod tracker;
+
+use crate::error::CliError;
+use crate::gitlog::{parse_git_log, place_commits};
+use crate::ics::{is_ics, parse_ics};
+use crate::input::{parse_delimited, Column, Columns, Label, Row};
+use crate::json::{is_json, is_trace, parse_json, parse_trace, Selectors};
+use crate::logfile::parse_log;
+use crate::overlap::Overlap;
+use crate::time::{duration, EpochUnit, TimeParser};
+use crate::tracker::parse_tracker;
+use clap::{App, Arg, ArgMatches};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tbl::logfile::LogPatterns;
+use tbl::{terminal, timetracker};
+use tbl::{Assignment, Block, Bound, Palette, RenderBlock, Renderer, SegmentStyle};
+
+const STYLES: [&str; 5] = ["plain", "ascii", "box", "rounded", "solid"];
+
+fn app() -> App<'static, 'static> {
+    App::new("tbl")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Render intervals (start, end, label, group rows) as a timeline")
+        .arg(
+            Arg::with_name("FILE")
+                .multiple(true)
+                .help(
+                    "Delimited (CSV, TSV...), JSON (array, JSON Lines), Chrome Trace Event files \
+                     (in microseconds), iCalendar files or timewarrior, watson and toggl exports \
+                     (unless columns are selected), logs with --log-timestamp, `git log` output with --git-log, stdin if none or `-`",
+                ),
+        )
+        .arg(
+            Arg::with_name("delimiter")
+                .short("d")
+                .long("delimiter")
+                .takes_value(true)
+                .help("Field delimiter, detected from the first line by default"),
+        )
+        .arg(
+            Arg::with_name("header")
+                .long("header")
+                .help("The first row is a header (implied when columns are selected by name)"),
+        )
+        .arg(column("start", "1"))
+        .arg(column("end", "2"))
+        .arg(column("label", "3"))
+        .arg(column("group", "4"))
+        .arg(
+            Arg::with_name("width")
+                .short("w")
+                .long("width")
+                .takes_value(true)
+                .validator(|w| is_number::<usize>(&w))
+                .help("Timeline width, terminal width by default"),
+        )
+        .arg(
+            Arg::with_name("from")
+                .long("from")
+                .takes_value(true)
+                .help("Start of the timeline, in the same format as bounds"),
+        )
+        .arg(
+            Arg::with_name("to")
+                .long("to")
+                .takes_value(true)
+                .help("End of the timeline, in the same format as bounds"),
+        )
+        .arg(
+            Arg::with_name("time-format")
+                .long("time-format")
+                .takes_value(true)
+                .help(
+                    "Bound format (`strftime` like, e.g. `%d/%m/%Y %H:%M`), \
+                     detected by default: epoch, ISO 8601 / RFC 3339 date or time of day",
+                ),
+        )
+        .arg(
+            Arg::with_name("epoch-unit")
+                .long("epoch-unit")
+                .takes_value(true)
+                .possible_values(&EpochUnit::VARIANTS)
+                .help("Unit of epoch bounds, detected from the magnitude of the first one by default"),
+        )
+        .arg(
+            Arg::with_name("attendees")
+                .long("attendees")
+                .help("Group iCalendar events by attendee instead of calendar"),
+        )
+        .arg(
+            Arg::with_name("log-timestamp")
+                .long("log-timestamp")
+                .takes_value(true)
+                .value_name("REGEX")
+                .help(
+                    "Read files as logs, timestamps are the first capture group (or the match) \
+                     of REGEX, lines without timestamp are ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("log-start")
+                .long("log-start")
+                .takes_value(true)
+                .value_name("REGEX")
+                .requires_all(&["log-timestamp", "log-end"])
+                .help(
+                    "Lines starting an interval, labelled by the first capture group (or the match), \
+                     unfinished intervals end with the last interval or event",
+                ),
+        )
+        .arg(
+            Arg::with_name("log-end")
+                .long("log-end")
+                .takes_value(true)
+                .value_name("REGEX")
+                .requires_all(&["log-timestamp", "log-start"])
+                .help("Lines ending the first unfinished interval with the same label"),
+        )
+        .arg(
+            Arg::with_name("log-event")
+                .long("log-event")
+                .takes_value(true)
+                .value_name("REGEX")
+                .requires("log-timestamp")
+                .help("Lines drawn as events, labelled by the first capture group (or the match)"),
+        )
+        .arg(
+            Arg::with_name("git-log")
+                .long("git-log")
+                .conflicts_with("log-timestamp")
+                .help(
+                    "Read files as `git log --format='%at%x09%an%x09%s'` output: \
+                     commit sessions and commits grouped by author",
+                ),
+        )
+        .arg(
+            Arg::with_name("git-gap")
+                .long("git-gap")
+                .takes_value(true)
+                .value_name("DURATION")
+                .default_value("2h")
+                .help("Consecutive commits of an author closer than DURATION (e.g. `30m`) are a session"),
+        )
+        .arg(
+            Arg::with_name("overlap")
+                .long("overlap")
+                .takes_value(true)
+                .possible_values(&Overlap::VARIANTS)
+                .default_value("split")
+                .help("Overlapping intervals (of the same group) are split on lanes, merged or an error"),
+        )
+        .arg(
+            Arg::with_name("style")
+                .long("style")
+                .takes_value(true)
+                .possible_values(&STYLES)
+                .help("Segment style"),
+        )
+        .arg(
+            Arg::with_name("labels")
+                .long("labels")
+                .conflicts_with("style")
+                .help("Write labels in segments"),
+        )
+        .arg(
+            Arg::with_name("palette")
+                .long("palette")
+                .help("Color segments by label and print a legend"),
+        )
+}
+
+fn column(name: &'static str, default: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .long(name)
+        .takes_value(true)
+        .value_name("COLUMN")
+        .default_value(default)
+        .help(
+            "Column index (from 1) or header name, \
+             JSON field selector (e.g. `$.span.start`, `[0]`) defaulting to the argument name",
+        )
+}
+
+fn is_number<T: std::str::FromStr>(value: &str) -> Result<(), String> {
+    value
+        .parse::<T>()
+        .map(|_| ())
+        .map_err(|_| format!("`{}` is not a number", value))
+}
+
+fn style(style: &str) -> SegmentStyle {
+    match style {
+        "ascii" => SegmentStyle::Ascii,
+        "box" => SegmentStyle::Box,
+        "rounded" => SegmentStyle::Rounded,
+        "solid" => SegmentStyle::Solid,
+        _ => SegmentStyle::Plain,
+    }
+}
+
+fn render_label(b: &Block<Label>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, label) => {
+            let text: String = label
+                .as_ref()
+                .map(|label| label.text.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - text.chars().count());
+            RenderBlock::Block(text + &padding)
+        }
+    }
+}
+
+/// Files (`-` for stdin) and their contents.
+fn read<'m>(matches: &'m ArgMatches) -> Result<Vec<(&'m str, String)>, CliError> {
+    let files: Vec<&str> = matches
+        .values_of("FILE")
+        .map_or(vec!["-"], Iterator::collect);
+    files
+        .into_iter()
+        .map(|file| {
+            if file == "-" {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text)?;
+                Ok((file, text))
+            } else {
+                Ok((file, fs::read_to_string(file)?))
+            }
+        })
+        .collect()
+}
+
+fn column_or_name(matches: &ArgMatches, name: &str) -> Column {
+    if matches.occurrences_of(name) > 0 {
+        Column::parse(matches.value_of(name).unwrap_or(name))
+    } else {
+        Column::Name(name.to_string())
+    }
+}
+
+/// Log patterns given on the command line, if any.
+fn log_patterns(matches: &ArgMatches) -> Result<Option<LogPatterns>, CliError> {
+    let pattern =
+        |name: &'static str, e: tbl::logfile::LogError| CliError::Argument(name, e.to_string());
+    let timestamp = match matches.value_of("log-timestamp") {
+        Some(timestamp) => timestamp,
+        None => return Ok(None),
+    };
+    let mut patterns = LogPatterns::new(timestamp).map_err(|e| pattern("log-timestamp", e))?;
+    if let (Some(start), Some(end)) = (matches.value_of("log-start"), matches.value_of("log-end")) {
+        patterns = patterns
+            .with_intervals(start, end)
+            .map_err(|e| pattern("log-start", e))?;
+    }
+    if let Some(event) = matches.value_of("log-event") {
+        patterns = patterns
+            .with_events(event)
+            .map_err(|e| pattern("log-event", e))?;
+    }
+    Ok(Some(patterns))
+}
+
+/// `--git-gap`: a duration, e.g. `30m`, `1h30m` or `90` (seconds).
+fn gap(gap: &str) -> Result<f64, CliError> {
+    duration(&format!("+{}", gap.trim_start_matches('+')))
+        .ok_or_else(|| CliError::Argument("git-gap", format!("`{}` is not a duration", gap)))
+}
+
+/// Rows, point events of logs and commits (see `markers`) and the number of skipped
+/// (malformed) JSON records, calendar events are limited to `window`.
+fn rows(
+    matches: &ArgMatches,
+    time: &mut TimeParser,
+    window: Option<Bound>,
+) -> Result<(Vec<Row>, Vec<Row>, usize), CliError> {
+    let columns = Columns {
+        start: Column::parse(matches.value_of("start").unwrap_or("1")),
+        end: Column::parse(matches.value_of("end").unwrap_or("2")),
+        label: Column::parse(matches.value_of("label").unwrap_or("3")),
+        group: Column::parse(matches.value_of("group").unwrap_or("4")),
+    };
+    let selectors = Selectors::new(&Columns {
+        start: column_or_name(matches, "start"),
+        end: column_or_name(matches, "end"),
+        label: column_or_name(matches, "label"),
+        group: column_or_name(matches, "group"),
+    });
+    let delimiter = matches.value_of("delimiter").map(|d| {
+        if d == "\\t" {
+            b'\t'
+        } else {
+            d.bytes().next().unwrap_or(b',')
+        }
+    });
+    let mut rows = vec![];
+    let mut points = vec![];
+    let mut skipped = 0;
+    let selected = ["start", "end", "label", "group"]
+        .iter()
+        .any(|name| matches.occurrences_of(name) > 0);
+    // running time entries end now, or at the end of the window if earlier
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0., |now| now.as_secs_f64());
+    let now = window.map_or(now, |(_, to)| to.min(now));
+    let log = log_patterns(matches)?;
+    let gap = gap(matches.value_of("git-gap").unwrap_or("2h"))?;
+    for (file, text) in read(matches)? {
+        let tracker = timetracker::detect(&text).filter(|_| !selected);
+        if matches.is_present("git-log") {
+            let (sessions, commits) = parse_git_log(&text, gap)?;
+            rows.extend(sessions);
+            points.extend(commits);
+        } else if let Some(patterns) = &log {
+            let (intervals, events) = parse_log(&text, patterns, time)?;
+            rows.extend(intervals);
+            points.extend(events);
+        } else if is_ics(&text) {
+            rows.extend(parse_ics(
+                &text,
+                file,
+                window,
+                matches.is_present("attendees"),
+            )?);
+        } else if let Some(tracker) = tracker {
+            rows.extend(parse_tracker(&text, tracker, now)?);
+        } else if !selected && is_trace(&text) {
+            rows.extend(parse_trace(&text)?);
+        } else if is_json(&text) {
+            let (json_rows, json_skipped) = parse_json(&text, &selectors, time)?;
+            rows.extend(json_rows);
+            skipped += json_skipped;
+        } else {
+            rows.extend(parse_delimited(
+                &text,
+                delimiter,
+                &columns,
+                matches.is_present("header"),
+                time,
+            )?);
+        }
+    }
+    Ok((rows, points, skipped))
+}
+
+/// Point events (empty rows) drawn one character wide on a `width` characters timeline
+/// between `boundaries`.
+fn markers(points: Vec<Row>, boundaries: Bound, width: usize) -> Vec<Row> {
+    let (a, b) = boundaries;
+    let width = width.max(1) as f64;
+    let cell = (b - a) / width;
+    points
+        .into_iter()
+        .map(|mut point| {
+            // across the next character boundary, so that it is not rounded to nothing
+            let character = ((point.start - a) / cell).floor().clamp(0., width - 1.);
+            point.start = a + (character + 0.25) * cell;
+            point.end = b.min(a + (character + 1.25) * cell);
+            point
+        })
+        .collect()
+}
+
+/// Window of `--from` and `--to`, unbounded on a missing side.
+fn window(from: Option<f64>, to: Option<f64>) -> Result<Option<Bound>, CliError> {
+    match (from, to) {
+        (None, None) => Ok(None),
+        (Some(from), Some(to)) if from >= to => Err(CliError::Argument(
+            "to",
+            "must be after the start of the timeline (--from)".to_string(),
+        )),
+        _ => Ok(Some((
+            from.unwrap_or(f64::NEG_INFINITY),
+            to.unwrap_or(f64::INFINITY),
+        ))),
+    }
+}
+
+/// Rows within `window`, clipped to it.
+fn clip(rows: Vec<Row>, window: Bound) -> Vec<Row> {
+    let (from, to) = window;
+    rows.into_iter()
+        .filter_map(|mut row| {
+            let (a, b) = (row.start.min(row.end), row.start.max(row.end));
+            let inside = (a < to && b > from) || (a == b && from <= a && a <= to);
+            row.start = a.max(from);
+            row.end = b.min(to);
+            Some(row).filter(|_| inside)
+        })
+        .collect()
+}
+
+/// Timeline boundaries of `rows` within `window`, `None` without rows nor window, an error if
+/// there is nothing to render in the window.
+fn boundaries<'r>(
+    rows: impl Iterator<Item = &'r Row>,
+    window: Option<Bound>,
+) -> Result<Option<Bound>, CliError> {
+    let (min, max) = rows.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), row| {
+        (min.min(row.start), max.max(row.end))
+    });
+    let (from, to) = match window {
+        Some(window) => window,
+        None if min > max => return Ok(None),
+        None => return Ok(Some((min, max))),
+    };
+    let boundaries = (
+        if from.is_finite() { from } else { min },
+        if to.is_finite() { to } else { max },
+    );
+    if boundaries.0 < boundaries.1 {
+        Ok(Some(boundaries))
+    } else {
+        let name = if from.is_finite() { "from" } else { "to" };
+        Err(CliError::Argument(
+            name,
+            "the timeline is empty".to_string(),
+        ))
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), CliError> {
+    let overlap = matches
+        .value_of("overlap")
+        .and_then(Overlap::parse)
+        .unwrap_or(Overlap::Split);
+    // --from, --to and bounds of the input are of the same kind and epoch unit
+    let mut time = TimeParser::new(
+        matches.value_of("time-format"),
+        matches.value_of("epoch-unit").and_then(EpochUnit::parse),
+    );
+    let mut bound = |name: &'static str| {
+        matches
+            .value_of(name)
+            .map(|b| {
+                time.bound(b)
+                    .map_err(|e| CliError::Argument(name, e.to_string()))
+            })
+            .transpose()
+    };
+    let from = bound("from")?;
+    let to = bound("to")?;
+    let window = window(from, to)?;
+    let (mut rows, mut points, skipped) = rows(matches, &mut time, window)?;
+    if let Some(window) = window {
+        rows = clip(rows, window);
+        points = clip(points, window);
+    }
+    let width = matches
+        .value_of("width")
+        .and_then(|w| w.parse().ok())
+        .unwrap_or_else(terminal::width);
+    let boundaries = match boundaries(rows.iter().chain(points.iter()), window)? {
+        // only simultaneous points: a second per character
+        Some((a, b)) if a >= b => Some((a, a + width.max(1) as f64)),
+        boundaries => boundaries.filter(|_| window.is_some() || !points.is_empty()),
+    };
+    if let Some(boundaries) = boundaries {
+        let markers = markers(points, boundaries, width);
+        if matches.is_present("git-log") {
+            rows = place_commits(rows, markers);
+        } else {
+            rows.extend(markers);
+        }
+    }
+    let rows = overlap::apply(rows, overlap)?;
+    let grouped = rows.iter().any(|row| row.label.group.is_some());
+
+    let group = |label: &Label| label.group.clone();
+    let category = |label: &Label| label.text.clone();
+    let mut renderer = Renderer::new(rows.as_slice(), &|row| (row.start, row.end), &|row| {
+        Some(row.label.clone())
+    });
+    let mut renderer = renderer.with_length(width);
+    if let Some(boundaries) = boundaries {
+        renderer = renderer.with_boundaries(boundaries);
+    }
+    if grouped {
+        renderer = renderer.with_groups(&group);
+    }
+    if matches.is_present("labels") {
+        renderer = renderer.with_renderer(&render_label);
+    }
+    if let Some(name) = matches.value_of("style") {
+        renderer = renderer.with_segment_style(style(name));
+    }
+    if matches.is_present("palette") {
+        renderer = renderer.with_palette(
+            Palette::colorblind_safe(Assignment::FirstAppearance),
+            &category,
+        );
+    }
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    renderer
+        .render_to(&mut out)
+        .map_err(|e| CliError::Render(e.to_string()))?;
+    for line in renderer.legend() {
+        writeln!(out, "{}", line)?;
+    }
+    if skipped > 0 {
+        eprintln!("tbl: skipped {} malformed records", skipped);
+    }
+    Ok(())
+}
+
+fn main() {
+    let matches = app().get_matches();
+    if let Err(e) = run(&matches) {
+        eprintln!("tbl: {}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::{Label, Row};
+    use crate::{boundaries, clip, gap, markers, window};
+
+    fn row(start: f64, end: f64) -> Row {
+        Row {
+            start,
+            end,
+            label: Label {
+                text: "a".to_string(),
+                group: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_window() {
+        assert!(window(None, None).unwrap().is_none());
+        assert_eq!(window(Some(1.), None).unwrap(), Some((1., f64::INFINITY)));
+        let error = window(Some(2.), Some(1.)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "--to: must be after the start of the timeline (--from)"
+        );
+        assert!(window(Some(1.), Some(1.)).is_err());
+    }
+
+    #[test]
+    fn test_clip() {
+        let rows = vec![row(0., 5.), row(5., 9.), row(6., 6.), row(10., 12.)];
+        let window = (2., 6.);
+        let clipped: Vec<(f64, f64)> = clip(rows, window)
+            .iter()
+            .map(|row| (row.start, row.end))
+            .collect();
+        assert_eq!(clipped, vec![(2., 5.), (5., 6.), (6., 6.)]);
+        let rows = [row(0., 5.)];
+        assert_eq!(
+            boundaries(rows.iter(), Some((2., f64::INFINITY))).unwrap(),
+            Some((2., 5.))
+        );
+        // nothing after --from, or no rows
+        assert!(boundaries(rows.iter(), Some((100., f64::INFINITY))).is_err());
+        assert!(boundaries([].iter(), Some((2., f64::INFINITY))).is_err());
+        assert_eq!(boundaries([].iter(), None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_markers() {
+        let points = vec![row(0., 0.), row(55., 55.), row(100., 100.)];
+        let markers: Vec<(f64, f64)> = markers(points, (0., 100.), 10)
+            .iter()
+            .map(|row| (row.start, row.end))
+            .collect();
+        // characters 0, 5 and 9
+        assert_eq!(markers, vec![(2.5, 12.5), (52.5, 62.5), (92.5, 100.)]);
+    }
+
+    #[test]
+    fn test_gap() {
+        assert_eq!(gap("30m").unwrap(), 1800.);
+        assert_eq!(gap("+1h30m").unwrap(), 5400.);
+        assert_eq!(gap("90").unwrap(), 90.);
+        assert_eq!(
+            gap("2 hours").unwrap_err().to_string(),
+            "--git-gap: `2 hours` is not a duration"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! What to do with overlapping intervals.
+
+use crate::error::CliError;
+use crate::input::{Label, Row};
+use itertools::Itertools;
+use std::cmp::Ordering;
+
+/// Overlap policy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Overlap {
+    /// Overlapping intervals are rendered on separate lanes.
+    Split,
+    /// Overlapping intervals (of the same group) are merged, labels are joined.
+    Merge,
+    /// Overlapping intervals (of the same group) are an error.
+    Error,
+}
+
+impl Overlap {
+    pub(crate) const VARIANTS: [&'static str; 3] = ["split", "merge", "error"];
+
+    pub(crate) fn parse(overlap: &str) -> Option<Overlap> {
+        match overlap {
+            "split" => Some(Overlap::Split),
+            "merge" => Some(Overlap::Merge),
+            "error" => Some(Overlap::Error),
+            _ => None,
+        }
+    }
+}
+
+fn by_start(left: &&Row, right: &&Row) -> Ordering {
+    left.start
+        .partial_cmp(&right.start)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Rows of each group (in order of first appearance) sorted by start.
+fn groups(rows: &[Row]) -> Vec<Vec<&Row>> {
+    rows.iter()
+        .map(|row| &row.label.group)
+        .unique()
+        .map(|group| {
+            rows.iter()
+                .filter(|row| &row.label.group == group)
+                .sorted_by(by_start)
+                .collect()
+        })
+        .collect()
+}
+
+fn merge(rows: Vec<&Row>) -> Vec<Row> {
+    let mut merged: Vec<(Row, Vec<String>)> = vec![];
+    for row in rows {
+        match merged.last_mut() {
+            Some((last, texts)) if row.start < last.end => {
+                last.end = last.end.max(row.end);
+                if !texts.contains(&row.label.text) {
+                    texts.push(row.label.text.clone());
+                }
+            }
+            _ => merged.push((row.clone(), vec![row.label.text.clone()])),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(row, texts)| Row {
+            label: Label {
+                text: texts.join(","),
+                group: row.label.group,
+            },
+            ..row
+        })
+        .collect()
+}
+
+/// Empty rows are not rendered, they never overlap.
+pub(crate) fn apply(rows: Vec<Row>, overlap: Overlap) -> Result<Vec<Row>, CliError> {
+    let (rows, empty): (Vec<Row>, Vec<Row>) =
+        rows.into_iter().partition(|row| row.start != row.end);
+    let rows = match overlap {
+        Overlap::Split => rows,
+        Overlap::Merge => groups(rows.as_slice())
+            .into_iter()
+            .flat_map(merge)
+            .collect(),
+        Overlap::Error => {
+            for group in groups(rows.as_slice()) {
+                if let Some((left, right)) = group
+                    .iter()
+                    .tuple_windows()
+                    .find(|(left, right)| right.start < left.end)
+                {
+                    return Err(CliError::Overlap(
+                        left.label.text.clone(),
+                        right.label.text.clone(),
+                    ));
+                }
+            }
+            rows
+        }
+    };
+    Ok(rows.into_iter().chain(empty).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::{Label, Row};
+    use crate::overlap::{apply, Overlap};
+
+    fn row(start: f64, end: f64, text: &str, group: Option<&str>) -> Row {
+        Row {
+            start,
+            end,
+            label: Label {
+                text: text.to_string(),
+                group: group.map(str::to_string),
+            },
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let rows = vec![
+            row(2., 4., "b", None),
+            row(0., 3., "a", None),
+            row(1., 2., "c", Some("g")),
+            row(4., 5., "a", None),
+        ];
+        let merged = apply(rows, Overlap::Merge).unwrap();
+        assert_eq!(
+            merged,
+            vec![
+                row(0., 4., "a,b", None),
+                row(4., 5., "a", None),
+                row(1., 2., "c", Some("g")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let rows = vec![row(0., 2., "a", None), row(2., 3., "b", Some("g"))];
+        assert!(apply(rows, Overlap::Error).is_ok());
+        // empty rows are not rendered
+        let rows = vec![row(0., 10., "a", None), row(5., 5., "b", None)];
+        assert!(apply(rows, Overlap::Error).is_ok());
+        let rows = vec![row(0., 2., "a", None), row(1., 3., "b", None)];
+        let error = apply(rows, Overlap::Error).unwrap_err();
+        assert_eq!(error.to_string(), "`a` overlaps `b`");
+    }
+}
@@ -0,0 +1,291 @@
+//! Timestamps and durations: bounds are converted to seconds.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::fmt;
+
+/// Unit of epoch timestamps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EpochUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl EpochUnit {
+    pub(crate) const VARIANTS: [&'static str; 4] = ["s", "ms", "us", "ns"];
+
+    pub(crate) fn parse(unit: &str) -> Option<EpochUnit> {
+        match unit {
+            "s" => Some(EpochUnit::Seconds),
+            "ms" => Some(EpochUnit::Millis),
+            "us" => Some(EpochUnit::Micros),
+            "ns" => Some(EpochUnit::Nanos),
+            _ => None,
+        }
+    }
+
+    /// Units per second.
+    fn per_second(self) -> f64 {
+        match self {
+            EpochUnit::Seconds => 1.,
+            EpochUnit::Millis => 1e3,
+            EpochUnit::Micros => 1e6,
+            EpochUnit::Nanos => 1e9,
+        }
+    }
+
+    /// Guess the unit of epoch timestamps from the magnitude of the first one (after year 5138
+    /// in seconds).
+    fn detect(value: f64) -> EpochUnit {
+        match value.abs() {
+            v if v >= 1e17 => EpochUnit::Nanos,
+            v if v >= 1e14 => EpochUnit::Micros,
+            v if v >= 1e11 => EpochUnit::Millis,
+            _ => EpochUnit::Seconds,
+        }
+    }
+}
+
+/// How a bound was written, all bounds of an input must be of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Epoch,
+    DateTime,
+    TimeOfDay,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Epoch => write!(f, "a number"),
+            Kind::DateTime => write!(f, "a date"),
+            Kind::TimeOfDay => write!(f, "a time of day"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum TimeError {
+    /// Not a bound nor a duration.
+    Invalid(String),
+    /// A bound of another kind than the previous ones.
+    Ambiguous(String),
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeError::Invalid(message) | TimeError::Ambiguous(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+fn seconds(datetime: NaiveDateTime) -> f64 {
+    let utc = datetime.and_utc();
+    utc.timestamp() as f64 + f64::from(utc.timestamp_subsec_nanos()) * 1e-9
+}
+
+fn time_of_day(time: NaiveTime) -> f64 {
+    f64::from(time.num_seconds_from_midnight()) + f64::from(time.nanosecond()) * 1e-9
+}
+
+/// `+1h30m`, `+90s`, `+1.5h`, `+2d`, `+500ms` or `+90` (seconds), finite and not negative.
+pub(crate) fn duration(text: &str) -> Option<f64> {
+    let text = text.strip_prefix('+')?;
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Some(seconds).filter(|seconds| seconds.is_finite() && *seconds >= 0.);
+    }
+    let mut total = 0.;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_end] {
+            "d" => 86_400.,
+            "h" => 3_600.,
+            "m" | "min" => 60.,
+            "s" => 1.,
+            "ms" => 1e-3,
+            _ => return None,
+        };
+        rest = &rest[unit_end..];
+        total += number * unit;
+    }
+    Some(total).filter(|total| total.is_finite())
+}
+
+/// Converts bounds to seconds: epoch timestamps, ISO 8601 / RFC 3339 dates (UTC if no offset),
+/// times of day (seconds since midnight) or `format` (`strftime` like).
+///
+/// The unit of epoch timestamps is detected from the first one unless given, then used for
+/// all bounds.
+pub(crate) struct TimeParser {
+    format: Option<String>,
+    unit: Option<EpochUnit>,
+    kind: Option<Kind>,
+}
+
+impl TimeParser {
+    pub(crate) fn new(format: Option<&str>, unit: Option<EpochUnit>) -> TimeParser {
+        TimeParser {
+            format: format.map(str::to_string),
+            unit,
+            kind: None,
+        }
+    }
+
+    fn parse(&mut self, text: &str) -> Option<(Kind, f64)> {
+        if let Some(format) = &self.format {
+            return DateTime::parse_from_str(text, format)
+                .map(|datetime| seconds(datetime.naive_utc()))
+                .or_else(|_| NaiveDateTime::parse_from_str(text, format).map(seconds))
+                .map(|seconds| (Kind::DateTime, seconds))
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(text, format)
+                        .map(|date| (Kind::DateTime, seconds(date.and_time(NaiveTime::MIN))))
+                })
+                .or_else(|_| {
+                    NaiveTime::parse_from_str(text, format)
+                        .map(|time| (Kind::TimeOfDay, time_of_day(time)))
+                })
+                .ok();
+        }
+        // not `inf` nor `nan`
+        if let Some(number) = text.parse::<f64>().ok().filter(|number| number.is_finite()) {
+            let unit = *self.unit.get_or_insert_with(|| EpochUnit::detect(number));
+            return Some((Kind::Epoch, number / unit.per_second()));
+        }
+        let datetime = DateTime::parse_from_rfc3339(text)
+            .map(|datetime| seconds(datetime.naive_utc()))
+            .ok()
+            .or_else(|| {
+                [
+                    "%Y-%m-%dT%H:%M:%S%.f",
+                    "%Y-%m-%d %H:%M:%S%.f",
+                    "%Y-%m-%dT%H:%M",
+                    "%Y-%m-%d %H:%M",
+                ]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                .map(seconds)
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| seconds(date.and_time(NaiveTime::MIN)))
+            });
+        if let Some(datetime) = datetime {
+            return Some((Kind::DateTime, datetime));
+        }
+        ["%H:%M:%S%.f", "%H:%M"]
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(text, format).ok())
+            .map(|time| (Kind::TimeOfDay, time_of_day(time)))
+    }
+
+    /// A bound in seconds.
+    pub(crate) fn bound(&mut self, text: &str) -> Result<f64, TimeError> {
+        let text = text.trim();
+        let (kind, seconds) = self
+            .parse(text)
+            .ok_or_else(|| TimeError::Invalid(format!("`{}` is not a time", text)))?;
+        match self.kind {
+            Some(previous) if previous != kind => Err(TimeError::Ambiguous(format!(
+                "`{}` is {}, previous bounds are {}",
+                text, kind, previous
+            ))),
+            _ => {
+                self.kind = Some(kind);
+                Ok(seconds)
+            }
+        }
+    }
+
+    /// An end in seconds: a bound or a duration (`+1h30m`) after `start`.
+    pub(crate) fn end(&mut self, text: &str, start: f64) -> Result<f64, TimeError> {
+        let text = text.trim();
+        if text.starts_with('+') {
+            duration(text)
+                .map(|duration| start + duration)
+                .ok_or_else(|| TimeError::Invalid(format!("`{}` is not a duration", text)))
+        } else {
+            self.bound(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::time::{duration, EpochUnit, TimeError, TimeParser};
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration("+1h30m"), Some(5400.));
+        assert_eq!(duration("+1.5h"), Some(5400.));
+        assert_eq!(duration("+2d500ms"), Some(172_800.5));
+        assert_eq!(duration("+90"), Some(90.));
+        assert_eq!(duration("+1y"), None);
+        assert_eq!(duration("1h"), None);
+        assert_eq!(duration("+inf"), None);
+        assert_eq!(duration("+-5"), None);
+        assert_eq!(duration(&format!("+{}h", "9".repeat(400))), None);
+    }
+
+    #[test]
+    fn test_auto() {
+        let mut parser = TimeParser::new(None, None);
+        assert_eq!(parser.bound("2024-05-01T10:03:00Z"), Ok(1_714_557_780.));
+        assert_eq!(
+            parser.bound("2024-05-01T12:03:00+02:00"),
+            Ok(1_714_557_780.)
+        );
+        assert_eq!(parser.bound("2024-05-01 10:03"), Ok(1_714_557_780.));
+        assert_eq!(parser.end("+1h", 1_714_557_780.), Ok(1_714_561_380.));
+        assert!(matches!(
+            parser.bound("10:03"),
+            Err(TimeError::Ambiguous(_))
+        ));
+        assert!(matches!(parser.bound("soon"), Err(TimeError::Invalid(_))));
+
+        let mut parser = TimeParser::new(None, None);
+        assert_eq!(parser.bound("1714557780123"), Ok(1_714_557_780.123));
+        // the unit is detected once
+        assert_eq!(parser.bound("12"), Ok(0.012));
+        assert_eq!(
+            parser.bound("10:03").unwrap_err().to_string(),
+            "`10:03` is a time of day, previous bounds are a number"
+        );
+        let mut parser = TimeParser::new(None, None);
+        assert_eq!(parser.bound("5"), Ok(5.));
+        assert_eq!(parser.bound("200000000000"), Ok(200_000_000_000.));
+
+        let mut parser = TimeParser::new(None, None);
+        assert_eq!(parser.bound("10:03"), Ok(36_180.));
+        assert_eq!(parser.bound("10:03:30.5"), Ok(36_210.5));
+
+        // not finite: neither a number nor a time, the unit is not detected from them
+        let mut parser = TimeParser::new(None, None);
+        for text in ["inf", "-inf", "nan", "infinity"].iter() {
+            assert!(matches!(parser.bound(text), Err(TimeError::Invalid(_))));
+        }
+        assert_eq!(parser.bound("5"), Ok(5.));
+    }
+
+    #[test]
+    fn test_explicit() {
+        let mut parser = TimeParser::new(None, Some(EpochUnit::Millis));
+        assert_eq!(parser.bound("1500"), Ok(1.5));
+        let mut parser = TimeParser::new(Some("%d/%m/%Y %H:%M"), None);
+        assert_eq!(parser.bound("01/05/2024 10:03"), Ok(1_714_557_780.));
+        assert!(parser.bound("2024-05-01T10:03:00Z").is_err());
+        let mut parser = TimeParser::new(Some("%Hh%M"), None);
+        assert_eq!(parser.bound("10h03"), Ok(36_180.));
+    }
+}
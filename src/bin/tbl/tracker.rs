@@ -0,0 +1,62 @@
+//! Time tracker exports input: timewarrior, watson and toggl.
+
+use crate::error::CliError;
+use crate::input::{Label, Row};
+use tbl::timetracker::{self, Tracker};
+
+/// Rows of time entries labelled by tags or description and grouped by project,
+/// running entries end at `now`.
+pub(crate) fn parse_tracker(text: &str, tracker: Tracker, now: f64) -> Result<Vec<Row>, CliError> {
+    Ok(timetracker::parse(text, tracker)?
+        .into_iter()
+        .map(|entry| {
+            let (start, end) = entry.bounds(now);
+            Row {
+                start,
+                end,
+                label: Label {
+                    text: entry.label,
+                    group: entry.group,
+                },
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::{Column, Columns};
+    use crate::json::{parse_json, Selectors};
+    use crate::time::TimeParser;
+    use crate::tracker::parse_tracker;
+    use tbl::timetracker::{self, Tracker};
+
+    #[test]
+    fn test_running_entries() {
+        let text = r#"[{"id": 1, "start": "20240501T100000Z", "tags": ["tbl", "docs"]}]"#;
+        let rows = parse_tracker(text, Tracker::Timewarrior, 1_714_561_200.).unwrap();
+        assert_eq!(rows[0].end - rows[0].start, 3600.);
+        // started after now: empty
+        let rows = parse_tracker(text, Tracker::Timewarrior, 0.).unwrap();
+        assert_eq!(rows[0].end, rows[0].start);
+    }
+
+    #[test]
+    fn test_plain_json() {
+        // read as JSON, not as a time tracker export
+        let text = r#"[{"start": 0, "end": 5, "label": "a"}]"#;
+        assert_eq!(timetracker::detect(text), None);
+        let name = |name: &str| Column::Name(name.to_string());
+        let selectors = Selectors::new(&Columns {
+            start: name("start"),
+            end: name("end"),
+            label: name("label"),
+            group: name("group"),
+        });
+        let (rows, _) = parse_json(text, &selectors, &mut TimeParser::new(None, None)).unwrap();
+        assert_eq!((rows[0].start, rows[0].end), (0., 5.));
+        let text = r#"[{"start": "2024-05-01T10:00:00Z", "end": "2024-05-01T11:00:00Z"}]"#;
+        assert_eq!(timetracker::detect(text), None);
+        assert_eq!(timetracker::detect(r#"[[0, 5, "a"]]"#), None);
+    }
+}
@@ -71,12 +71,12 @@ where
             let none_delimited = intervals.iter().map(Some).chain(iter::once(None));
             let windowed = none_delimited.tuple_windows::<(_, _)>();
             windowed
-                .map(|(left, right)| match (left, right) {
+                .flat_map(|(left, right)| match (left, right) {
                     (Some(&left_interval), Some(&right_interval)) => {
                         iter::once(TBLBlock::Segment(left_interval.clone()))
                             .chain(iter::once(TBLBlock::Space(space_between(
-                                &left_interval,
-                                &right_interval,
+                                left_interval,
+                                right_interval,
                             ))))
                             .collect()
                     }
@@ -85,7 +85,6 @@ where
                     }
                     _ => iter::empty().collect::<Vec<TBLBlock<L>>>(),
                 })
-                .flatten()
                 .collect()
         }
     };
@@ -1,11 +1,109 @@
 //! A Renderer builds `Blocks` from provided intervals and render them.
 
+use crate::ansi::{strip, ColorLevel};
 use crate::blocks::build_blocks;
-use crate::interval::{boundaries, is_empty, is_finite, split_overlapping, union, TBLInterval};
-use crate::rendering::{render_blocks, render_default, DEFAULT_LENGTH};
+use crate::calendar::{render_calendar, CalendarTime, Shading, MAX_WEEKS};
+use crate::html::render_html;
+use crate::interval::TBLInterval;
+use crate::layout::{grouped, layout, GroupKey, Lane, Layout, Segment};
+use crate::markdown::render_markdown;
+use crate::options::Options;
+use crate::palette::{colorize, Categories, CategorySets, LegendEntry, Palette};
+use crate::pattern::{fills, Fill, Patterns};
+use crate::rendering::{render_blocks, render_default, styled_lines, DEFAULT_LENGTH};
+use crate::segment::SegmentStyle;
+use crate::style::{fit, plain, Color, Line, Span, Style};
+use crate::svg::render_svg;
+#[cfg(feature = "terminal")]
+use crate::terminal;
+use crate::vertical::{join_columns, segment_rows, Orientation, DEFAULT_LANE_WIDTH};
+use crate::wrap::{clip, overwrite, unclip, windows, Clipped, Wrap, MAX_WINDOWS};
 use crate::{Block, Bound, RenderBlock, TBLError};
-use itertools::Itertools;
-use std::fmt::Debug;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::fmt::{self, Debug};
+use std::io;
+
+/// Renders a block, see `Renderer::with_renderer`.
+type BlockRenderer<'a, L> = &'a dyn Fn(&Block<L>) -> RenderBlock;
+/// Returns the category of a label.
+type CategoryKey<'a, L> = &'a dyn Fn(&L) -> String;
+/// Returns the pattern of a label, `None` without category.
+type LabelPattern<'a, L> = &'a dyn Fn(&L) -> Option<char>;
+/// Returns the text of a label.
+type LabelFormat<'a, L> = &'a dyn Fn(&L) -> String;
+/// Returns the text of a bound or a duration.
+type BoundFormat<'a> = &'a dyn Fn(f64) -> String;
+/// Returns the header of a wrapped row from its window.
+type WindowHeader<'a> = &'a dyn Fn(Bound) -> String;
+
+/// Color of segments without category nor styled renderer (blue grey).
+const DEFAULT_SEGMENT_COLOR: Color = Color::Rgb(96, 125, 139);
+
+fn debug_label<L: Debug>(label: &L) -> String {
+    format!("{:?}", label)
+}
+
+fn display_bound(bound: f64) -> String {
+    format!("{}", bound)
+}
+
+/// Render a block: filled with its pattern `fill` or its segment style, otherwise by the block
+/// renderer, then colored with the `color` of its label.
+fn style_block<M: Clone + Debug>(
+    block: &Block<M>,
+    fill: Option<&Fill>,
+    segment_style: Option<SegmentStyle>,
+    renderer: Option<BlockRenderer<M>>,
+    color: Option<Color>,
+) -> RenderBlock {
+    let rendered = match (block, fill, segment_style) {
+        (Block::Segment(length, _), Some(fill), None | Some(SegmentStyle::Plain)) => {
+            RenderBlock::Block(fill.render(*length))
+        }
+        (Block::Segment(length, _), fill, Some(style)) => {
+            RenderBlock::Block(style.render(*length, fill.map(Fill::pattern)))
+        }
+        _ => match renderer {
+            Some(renderer) => renderer(block),
+            None => render_default(block),
+        },
+    };
+    match color {
+        Some(color) => RenderBlock::StyledBlock(
+            styled_lines(&rendered)
+                .iter()
+                .map(|line| colorize(line, color))
+                .collect(),
+        ),
+        None => rendered,
+    }
+}
+
+/// Lines of the blocks of a lane, `pattern` (if patterns are used) and `color` of labels.
+fn lane_lines<M: Clone + Debug>(
+    blocks: &[Block<M>],
+    segment_style: Option<SegmentStyle>,
+    renderer: Option<BlockRenderer<M>>,
+    pattern: Option<LabelPattern<M>>,
+    color: &dyn Fn(&M) -> Option<Color>,
+) -> Vec<Line> {
+    let fills = pattern.map_or_else(Vec::new, |pattern| fills(blocks, pattern));
+    let renderer = |i: usize, block: &Block<M>| {
+        let color = match block {
+            Block::Segment(_, Some(label)) => color(label),
+            _ => None,
+        };
+        style_block(
+            block,
+            fills.get(i).and_then(Option::as_ref),
+            segment_style,
+            renderer,
+            color,
+        )
+    };
+    render_blocks(blocks, &renderer)
+}
 
 /// Render intervals.
 ///
@@ -16,8 +114,21 @@ where
 {
     length: usize,
     intervals: Vec<TBLInterval<L>>,
-    renderer: &'a dyn Fn(&Block<L>) -> RenderBlock,
+    /// `render_default` if none.
+    renderer: Option<BlockRenderer<'a, L>>,
     boundaries: Option<Bound>,
+    color_level: Option<ColorLevel>,
+    palette: Option<(Palette, CategoryKey<'a, L>)>,
+    patterns: Option<(Patterns, CategoryKey<'a, L>)>,
+    segment_style: Option<SegmentStyle>,
+    group: Option<GroupKey<'a, L>>,
+    /// `{:?}` if none, vertical segments are then not labelled.
+    label_format: Option<LabelFormat<'a, L>>,
+    bound_format: BoundFormat<'a>,
+    duration_format: BoundFormat<'a>,
+    orientation: Orientation,
+    lane_width: usize,
+    wrap: Option<(Wrap, WindowHeader<'a>)>,
 }
 
 impl<'a, L> Renderer<'a, L>
@@ -51,8 +162,19 @@ where
                 .iter()
                 .map(|interval| TBLInterval::new(fb(interval), fl(interval)))
                 .collect(),
-            renderer: &render_default,
+            renderer: None,
             boundaries: None,
+            color_level: None,
+            palette: None,
+            patterns: None,
+            segment_style: None,
+            group: None,
+            label_format: None,
+            bound_format: &display_bound,
+            duration_format: &display_bound,
+            orientation: Orientation::Horizontal,
+            lane_width: DEFAULT_LANE_WIDTH,
+            wrap: None,
         }
     }
 
@@ -79,6 +201,29 @@ where
         self
     }
 
+    /// Size `Renderer` length to the terminal width (see `terminal::width`) minus `gutter`.
+    ///
+    /// `gutter` is the number of columns printed by the caller around each line
+    /// (e.g. row headers, right margin annotations) so that the whole output fits.
+    ///
+    /// ```no_run
+    /// use tbl::{Bound, Renderer};
+    /// let data: Vec<Bound> = vec![(1., 2.)];
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_terminal_length(10)
+    ///     .render()
+    ///     .unwrap();
+    /// // 10 columns left for a row header
+    /// for line in rendered.iter().flatten() {
+    ///     println!("{:>9} {}", "interval", line);
+    /// }
+    /// ```
+    #[cfg(feature = "terminal")]
+    pub fn with_terminal_length(&'a mut self, gutter: usize) -> &'a mut Renderer<'a, L> {
+        self.length = terminal::width().saturating_sub(gutter).max(1);
+        self
+    }
+
     /// Configure `Renderer` intervals boundaries.
     ///
     /// if provided boundaries is not a sub-boundary of provided intervals then
@@ -136,14 +281,407 @@ where
     ///     assert_eq!(line, "(1.0, 2.0)★★★★★★★★★★☆☆☆☆☆☆☆☆☆☆☆☆☆☆☆☆☆☆☆☆(3.0, 4.0)★★★★★★★★★★");
     /// }
     /// ```
-    pub fn with_renderer(
+    pub fn with_renderer(&'a mut self, renderer: BlockRenderer<'a, L>) -> &'a mut Renderer<'a, L> {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// Configure colors supported by the output terminal.
+    ///
+    /// By default the color level is detected from the environment (see `ColorLevel::detect`).
+    ///
+    /// ```
+    /// use tbl::{Block, Bound, Color, ColorLevel, RenderBlock, Renderer, Span, Style};
+    ///
+    /// fn render(b: &Block<String>) -> RenderBlock {
+    ///     match b {
+    ///         Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+    ///         Block::Segment(length, _) => RenderBlock::StyledBlock(vec![vec![Span::styled(
+    ///             " ".repeat(*length),
+    ///             Style::new().bg(Color::Rgb(3, 169, 244)),
+    ///         )]]),
+    ///     }
+    /// }
+    /// let data: Vec<Bound> = vec![(1., 2.)];
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_length(2)
+    ///     .with_renderer(&render)
+    ///     .with_color_level(ColorLevel::Ansi256)
+    ///     .render();
+    /// for line in rendered.unwrap().iter().flatten() {
+    ///     assert_eq!(line, "\u{1b}[48;5;39m  \u{1b}[0m");
+    /// }
+    /// ```
+    pub fn with_color_level(&'a mut self, color_level: ColorLevel) -> &'a mut Renderer<'a, L> {
+        self.color_level = Some(color_level);
+        self
+    }
+
+    /// Color segments using a categorical palette.
+    ///
+    /// `key` returns the category of a label, each distinct key gets a color from `palette`.
+    /// Colors are applied as background to spans without background.
+    ///
+    /// ```
+    /// use tbl::{style, Assignment, Bound, Color, Palette, Renderer};
+    /// let data: Vec<Bound> = vec![(1., 2.), (3., 4.), (5., 6.)];
+    /// let palette = Palette::new(vec![Color::Red, Color::Blue], Assignment::FirstAppearance);
+    /// let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|&(a, _)| {
+    ///     Some(if a < 4. { "work" } else { "lunch" }.to_string())
+    /// });
+    /// let renderer = renderer.with_length(10).with_palette(palette, &|label| label.clone());
+    /// let rendered = renderer.render_styled().unwrap();
+    /// let line = &rendered[0][0];
+    /// assert_eq!(style::plain(line), "==  ==  ==");
+    /// assert_eq!(line[0].style.bg, Some(Color::Red));
+    /// assert_eq!(line.last().unwrap().style.bg, Some(Color::Blue));
+    /// let legend = renderer.legend_styled();
+    /// assert_eq!(style::plain(&legend[0]), "   work");
+    /// assert_eq!(style::plain(&legend[1]), "   lunch");
+    /// ```
+    pub fn with_palette(
+        &'a mut self,
+        palette: Palette,
+        key: CategoryKey<'a, L>,
+    ) -> &'a mut Renderer<'a, L> {
+        self.palette = Some((palette, key));
+        self
+    }
+
+    /// Fill segments using a distinct pattern per category.
+    ///
+    /// `key` returns the category of a label, each distinct key gets a pattern from `patterns`.
+    /// It is independent of the key given to `Renderer::with_palette`.
+    /// The block renderer is only used for spaces.
+    ///
+    /// Touching segments with the same pattern are separated using `│`, one column wide segments
+    /// are only the separator.
+    ///
+    /// ```
+    /// use tbl::{Bound, Patterns, Renderer};
+    /// let data: Vec<Bound> = vec![(0., 1.), (1., 2.), (2., 3.), (4., 5.)];
+    /// let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|&(a, _)| {
+    ///     Some(if a < 2. { "work" } else { "lunch" }.to_string())
+    /// });
+    /// let renderer = renderer
+    ///     .with_length(10)
+    ///     .with_patterns(Patterns::default(), &|label| label.clone());
+    /// let rendered = renderer.render().unwrap();
+    /// assert_eq!(rendered[0][0], "██│█▓▓  ▓▓");
+    /// assert_eq!(renderer.legend(), vec!["██ work", "▓▓ lunch"]);
+    /// ```
+    pub fn with_patterns(
+        &'a mut self,
+        patterns: Patterns,
+        key: CategoryKey<'a, L>,
+    ) -> &'a mut Renderer<'a, L> {
+        self.patterns = Some((patterns, key));
+        self
+    }
+
+    /// Render segments using a built-in style, the block renderer is only used for spaces.
+    ///
+    /// Caps show where touching segments start and end, combine with `Renderer::with_patterns`
+    /// to fill segments with patterns.
+    ///
+    /// ```
+    /// use tbl::{Bound, Renderer, SegmentStyle};
+    /// let data: Vec<Bound> = vec![(0., 3.), (3., 6.), (7., 8.)];
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_length(8)
+    ///     .with_segment_style(SegmentStyle::Box)
+    ///     .render();
+    /// for line in rendered.unwrap().iter().flatten() {
+    ///     assert_eq!(line, "├─┤├─┤ │");
+    /// }
+    /// ```
+    pub fn with_segment_style(&'a mut self, style: SegmentStyle) -> &'a mut Renderer<'a, L> {
+        self.segment_style = Some(style);
+        self
+    }
+
+    /// Group intervals in swimlanes.
+    ///
+    /// `group` returns the group of a label, groups are rendered in order of first appearance,
+    /// each group is split into its own non overlapping lanes.
+    /// Intervals without group are rendered together.
+    ///
+    /// ```
+    /// use tbl::Renderer;
+    /// let data = vec![(0., 2., "alice"), (1., 3., "bob"), (2., 4., "alice")];
+    /// let rendered = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+    ///     Some(l.to_string())
+    /// })
+    /// .with_length(4)
+    /// .with_groups(&|label| Some(label.clone()))
+    /// .render()
+    /// .unwrap();
+    /// assert_eq!(rendered, vec![vec!["===="], vec![" == "]]);
+    /// ```
+    pub fn with_groups(&'a mut self, group: GroupKey<'a, L>) -> &'a mut Renderer<'a, L> {
+        self.group = Some(group);
+        self
+    }
+
+    /// Text of labels for text based backends (e.g. SVG), `{:?}` by default.
+    ///
+    /// Vertical segments are only labelled using a label format, see `Renderer::with_orientation`.
+    pub fn with_label_format(
+        &'a mut self,
+        label_format: LabelFormat<'a, L>,
+    ) -> &'a mut Renderer<'a, L> {
+        self.label_format = Some(label_format);
+        self
+    }
+
+    /// Text of bounds for text based backends (e.g. SVG axis), `{}` by default.
+    pub fn with_bound_format(
+        &'a mut self,
+        bound_format: BoundFormat<'a>,
+    ) -> &'a mut Renderer<'a, L> {
+        self.bound_format = bound_format;
+        self
+    }
+
+    /// Text of durations for text based backends (e.g. HTML tooltips), `{}` by default.
+    pub fn with_duration_format(
+        &'a mut self,
+        duration_format: BoundFormat<'a>,
+    ) -> &'a mut Renderer<'a, L> {
+        self.duration_format = duration_format;
+        self
+    }
+
+    /// Configure `Renderer` orientation.
+    ///
+    /// When vertical, time runs down `length` rows and each lane is a column
+    /// (see `Renderer::with_lane_width`). Each row of a segment is rendered by the block
+    /// renderer (see `Renderer::with_renderer`), only the first one is given the label.
+    /// With the default block renderer, patterns or a segment style, segments are filled and
+    /// labels (see `Renderer::with_label_format`) are written on their first row, segment
+    /// styles cap their first and last rows (e.g. `┬` and `┴` for `SegmentStyle::Box`).
+    /// `Renderer::render` then returns a single timeline.
+    ///
+    /// ```
+    /// use tbl::{Orientation, Renderer};
+    /// let data = vec![(0., 2., "ab"), (1., 3., "cd"), (3., 4., "ef")];
+    /// let rendered = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+    ///     Some(l.to_string())
+    /// })
+    /// .with_length(4)
+    /// .with_orientation(Orientation::Vertical)
+    /// .with_lane_width(3)
+    /// .with_label_format(&|label| label.clone())
+    /// .render()
+    /// .unwrap();
+    /// assert_eq!(rendered, vec![vec!["    ab=", "cd= ===", "===    ", "ef=    "]]);
+    /// ```
+    pub fn with_orientation(&'a mut self, orientation: Orientation) -> &'a mut Renderer<'a, L> {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Width (characters) of lane columns when vertical, see `Renderer::with_orientation`.
+    pub fn with_lane_width(&'a mut self, lane_width: usize) -> &'a mut Renderer<'a, L> {
+        self.lane_width = lane_width;
+        self
+    }
+
+    /// Wrap the timeline across several rows, e.g. one row per day.
+    ///
+    /// The domain is cut into consecutive windows (see `Wrap`), each window is rendered
+    /// on its own scale as a header line (`header` of the window) followed by its lanes.
+    /// Segments crossing a window boundary are split, continued ends are marked `«` and `»`.
+    /// Only applies to `Orientation::Horizontal`.
+    /// Rendering fails with `TBLError::TooManyRows` beyond 100 000 windows.
+    ///
+    /// ```
+    /// use tbl::{Bound, Renderer, Wrap};
+    /// let data: Vec<Bound> = vec![(1., 3.), (6., 14.), (17., 18.)];
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_length(10)
+    ///     .with_wrap(Wrap::Span(10.), &|(a, b)| format!("{}..{}", a, b))
+    ///     .render()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rendered,
+    ///     vec![vec!["0..10", " ==   ===»"], vec!["10..20", "«===   =  "]]
+    /// );
+    /// ```
+    pub fn with_wrap(
         &'a mut self,
-        renderer: &'a dyn Fn(&Block<L>) -> RenderBlock,
+        wrap: Wrap,
+        header: WindowHeader<'a>,
     ) -> &'a mut Renderer<'a, L> {
-        self.renderer = renderer;
+        self.wrap = Some((wrap, header));
         self
     }
 
+    /// Apply `options`, unset options are left unchanged.
+    ///
+    /// ```
+    /// use tbl::{Bound, Options, Renderer, SegmentStyle};
+    /// let data: Vec<Bound> = vec![(0., 3.), (4., 5.)];
+    /// let options = Options {
+    ///     length: Some(5),
+    ///     segment_style: Some(SegmentStyle::Ascii),
+    ///     ..Options::default()
+    /// };
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_options(&options)
+    ///     .render()
+    ///     .unwrap();
+    /// assert_eq!(rendered, vec![vec!["[=] |"]]);
+    /// ```
+    pub fn with_options(&'a mut self, options: &Options) -> &'a mut Renderer<'a, L> {
+        if let Some(length) = options.length {
+            self.length = length;
+        }
+        if let Some(boundaries) = options.boundaries {
+            self.boundaries = Some(boundaries);
+        }
+        if let Some(color_level) = options.color_level {
+            self.color_level = Some(color_level);
+        }
+        if let Some(segment_style) = options.segment_style {
+            self.segment_style = Some(segment_style);
+        }
+        if let Some(orientation) = options.orientation {
+            self.orientation = orientation;
+        }
+        if let Some(lane_width) = options.lane_width {
+            self.lane_width = lane_width;
+        }
+        self
+    }
+
+    /// Legend: one line per category, a swatch followed by the category key.
+    ///
+    /// The swatch shows the category color and pattern.
+    /// Empty if no palette nor patterns are configured,
+    /// see `Renderer::with_palette` and `Renderer::with_patterns`.
+    pub fn legend_styled(&self) -> Vec<Line> {
+        self.legend_entries()
+            .into_iter()
+            .map(|entry| {
+                let swatch = entry.pattern.unwrap_or(' ').to_string().repeat(2);
+                let style = match entry.color {
+                    Some(color) => Style::new().bg(color),
+                    None => Style::new(),
+                };
+                vec![
+                    Span::styled(swatch, style),
+                    Span::raw(" "),
+                    Span::raw(entry.key),
+                ]
+            })
+            .collect()
+    }
+
+    /// Palette categories then pattern categories, entries with the same key are merged.
+    fn legend_entries(&self) -> Vec<LegendEntry> {
+        let categories = self.categories();
+        let keys = categories
+            .colors
+            .keys()
+            .iter()
+            .chain(categories.patterns.keys());
+        Categories::new(keys.map(String::as_str))
+            .keys()
+            .iter()
+            .map(|key| LegendEntry {
+                key: key.clone(),
+                color: categories
+                    .colors
+                    .index(key)
+                    .and_then(|index| self.color(key, index)),
+                pattern: categories
+                    .patterns
+                    .index(key)
+                    .and_then(|index| self.pattern(index)),
+            })
+            .collect()
+    }
+
+    /// Legend rendered using ANSI escape codes, see `Renderer::legend_styled`.
+    pub fn legend(&self) -> Vec<String> {
+        let color_level = self.color_level.unwrap_or_else(ColorLevel::detect);
+        self.legend_styled()
+            .iter()
+            .map(|line| color_level.paint(line))
+            .collect()
+    }
+
+    fn categories(&self) -> CategorySets {
+        let categories = |key: Option<CategoryKey<'a, L>>| match key {
+            None => Categories::default(),
+            Some(key) => {
+                let keys: Vec<String> = self
+                    .intervals
+                    .iter()
+                    .filter_map(|interval| interval.label.as_ref().map(key))
+                    .collect();
+                Categories::new(keys.iter().map(String::as_str))
+            }
+        };
+        CategorySets {
+            colors: categories(self.palette.as_ref().map(|(_, key)| *key)),
+            patterns: categories(self.patterns.as_ref().map(|(_, key)| *key)),
+        }
+    }
+
+    fn color(&self, key: &str, index: usize) -> Option<Color> {
+        self.palette
+            .as_ref()
+            .and_then(|(palette, _)| palette.color(key, index))
+    }
+
+    fn pattern(&self, index: usize) -> Option<char> {
+        self.patterns
+            .as_ref()
+            .and_then(|(patterns, _)| patterns.pattern(index))
+    }
+
+    fn label_color(&self, label: &L, categories: &CategorySets) -> Option<Color> {
+        self.palette.as_ref().and_then(|(_, key)| {
+            let key = key(label);
+            categories
+                .colors
+                .index(&key)
+                .and_then(|index| self.color(&key, index))
+        })
+    }
+
+    /// Pattern of a label, `None` without category.
+    fn label_pattern(&self, label: &L, categories: &CategorySets) -> Option<char> {
+        self.patterns.as_ref().and_then(|(patterns, key)| {
+            categories
+                .patterns
+                .index(&key(label))
+                .and_then(|index| patterns.pattern(index))
+        })
+    }
+
+    fn fills(&self, blocks: &[Block<L>], categories: &CategorySets) -> Vec<Option<Fill>> {
+        match &self.patterns {
+            Some(_) => fills(blocks, &|label| self.label_pattern(label, categories)),
+            None => vec![],
+        }
+    }
+
+    fn render_block(
+        &self,
+        block: &Block<L>,
+        categories: &CategorySets,
+        fill: Option<&Fill>,
+    ) -> RenderBlock {
+        let color = match block {
+            Block::Segment(_, Some(label)) => self.label_color(label, categories),
+            _ => None,
+        };
+        style_block(block, fill, self.segment_style, self.renderer, color)
+    }
+
     /// Render intervals as a `Vec<Vec<String>>`.
     ///
     /// 1. Overlapping intervals are split into non overlapping subsets.
@@ -151,6 +689,9 @@ where
     ///
     /// ie `Vec<Vec<String>>` is a vec of (multiline) timelines.
     ///
+    /// Styled spans (see `RenderBlock::StyledBlock`) are rendered using ANSI escape codes,
+    /// see `Renderer::with_color_level`.
+    ///
     /// ```
     /// use tbl::{Bound, Renderer};
     /// let data: Vec<Bound> = vec![(1., 2.), (3., 4.)]; // T = (f64, f64)
@@ -162,38 +703,524 @@ where
     /// }
     /// ```
     pub fn render(&self) -> Result<Vec<Vec<String>>, TBLError<L>> {
-        let sorted_intervals: Vec<TBLInterval<L>> = self
-            .intervals
+        let rendered = self.render_styled()?;
+        let color_level = self.color_level.unwrap_or_else(ColorLevel::detect);
+        Ok(rendered
             .iter()
-            .filter(|interval| is_finite(interval))
-            .filter(|interval| !is_empty(interval))
-            .sorted()
-            .cloned()
-            .collect();
-        let boundaries = match (boundaries(sorted_intervals.as_slice()), self.boundaries) {
-            (None, _) => self.boundaries,
-            (Some(b), None) => Some(b),
-            (Some(b), Some(other)) => Some(union(&b, &other)),
+            .map(|lines| lines.iter().map(|line| color_level.paint(line)).collect())
+            .collect())
+    }
+
+    /// Render intervals as styled lines, see `Renderer::render`.
+    ///
+    /// ```
+    /// use tbl::{style, Block, Bound, Color, RenderBlock, Renderer, Span, Style};
+    ///
+    /// fn render(b: &Block<String>) -> RenderBlock {
+    ///     match b {
+    ///         Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+    ///         Block::Segment(_, label) => RenderBlock::StyledBlock(vec![vec![Span::styled(
+    ///             label.clone().unwrap_or_default(),
+    ///             Style::new().bg(Color::Blue),
+    ///         )]]),
+    ///     }
+    /// }
+    /// let data: Vec<Bound> = vec![(1., 2.), (3., 4.)];
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| Some("label".to_string()))
+    ///     .with_length(12)
+    ///     .with_renderer(&render)
+    ///     .render_styled()
+    ///     .unwrap();
+    /// for line in rendered.iter().flatten() {
+    ///     assert_eq!(style::width(line), 12);
+    ///     assert_eq!(style::plain(line), "labe    labe");
+    /// }
+    /// ```
+    pub fn render_styled(&self) -> Result<Vec<Vec<Line>>, TBLError<L>> {
+        let mut rendered = vec![];
+        self.render_timelines(&mut |lines| {
+            rendered.push(lines);
+            Ok(())
+        })?;
+        Ok(rendered)
+    }
+
+    /// Render intervals to `out`, one line per (multiline) timeline line, see `Renderer::render`.
+    ///
+    /// Lines are written as soon as each lane is rendered.
+    ///
+    /// ```
+    /// use tbl::{Bound, Renderer};
+    /// let data: Vec<Bound> = vec![(1., 3.), (2., 4.)];
+    /// let mut out: Vec<u8> = vec![];
+    /// Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_length(6)
+    ///     .render_to(&mut out)
+    ///     .unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "  ====\n====  \n");
+    /// ```
+    pub fn render_to(&self, out: &mut impl io::Write) -> Result<(), TBLError<L>> {
+        let color_level = self.color_level.unwrap_or_else(ColorLevel::detect);
+        self.render_timelines(&mut |lines| {
+            for line in lines {
+                writeln!(out, "{}", color_level.paint(&line))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Render intervals to a `fmt::Write` (e.g. a `String`), see `Renderer::render_to`.
+    pub fn render_to_fmt(&self, out: &mut impl fmt::Write) -> Result<(), TBLError<L>> {
+        let color_level = self.color_level.unwrap_or_else(ColorLevel::detect);
+        self.render_timelines(&mut |lines| {
+            for line in lines {
+                writeln!(out, "{}", color_level.paint(&line))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Render (multiline) timelines one at a time into `sink`.
+    fn render_timelines(
+        &self,
+        sink: &mut dyn FnMut(Vec<Line>) -> Result<(), TBLError<L>>,
+    ) -> Result<(), TBLError<L>> {
+        let categories = self.categories();
+        if self.orientation == Orientation::Vertical {
+            return self
+                .render_vertical(&self.layout(), &categories)?
+                .into_iter()
+                .try_for_each(sink);
+        }
+        // lanes are split and rendered one at a time
+        let grouped = grouped(self.intervals.as_slice(), self.boundaries, self.group);
+        let boundaries = grouped.boundaries;
+        if let Some((wrap, header)) = self.wrap {
+            return self.render_wrapped(boundaries, &categories, wrap, header, sink);
+        }
+        let render = |lane: &Lane<L>| -> Result<Vec<Line>, TBLError<L>> {
+            let blocks: Vec<Block<L>> =
+                build_blocks(lane.intervals().as_slice(), self.length, boundaries)?
+                    .into_iter()
+                    .map(Block::from)
+                    .collect();
+            Ok(self.render_lane(blocks.as_slice(), &categories))
         };
-        let non_overlapping_subsets = split_overlapping(sorted_intervals.as_slice());
-        let rendered: Vec<Vec<String>> = non_overlapping_subsets
-            .iter()
-            .map(
-                |intervals| match build_blocks(intervals.as_slice(), self.length, boundaries) {
-                    Err(e) => Err(e),
-                    Ok(blocks) => {
-                        let blocks: Vec<Block<L>> =
-                            blocks.iter().map(|b| Block::from(b.clone())).collect();
-                        let rendered = render_blocks(blocks.as_slice(), self.renderer);
-                        let rendered = rendered
-                            .iter()
-                            .map(|v| v.iter().map(String::from).collect())
-                            .collect::<Vec<String>>();
-                        Ok(rendered)
+        for lane in grouped.into_lanes() {
+            sink(render(&lane)?)?;
+        }
+        Ok(())
+    }
+
+    fn render_lane(&self, blocks: &[Block<L>], categories: &CategorySets) -> Vec<Line> {
+        let pattern = |label: &L| self.label_pattern(label, categories);
+        let color = |label: &L| self.label_color(label, categories);
+        lane_lines(
+            blocks,
+            self.segment_style,
+            self.renderer,
+            self.patterns
+                .as_ref()
+                .map(|_| &pattern as &dyn Fn(&L) -> Option<char>),
+            &color,
+        )
+    }
+
+    fn render_wrapped(
+        &self,
+        boundaries: Option<Bound>,
+        categories: &CategorySets,
+        wrap: Wrap,
+        header: WindowHeader<'a>,
+        sink: &mut dyn FnMut(Vec<Line>) -> Result<(), TBLError<L>>,
+    ) -> Result<(), TBLError<L>> {
+        let boundaries = match boundaries {
+            Some(boundaries) => boundaries,
+            None => return Err(TBLError::NoBoundaries),
+        };
+        let (windows, width) = windows(wrap, boundaries, self.length)
+            .map_err(|count| TBLError::TooManyRows(count, MAX_WINDOWS))?;
+        let group = |clipped: &Clipped<L>| {
+            let group = self.group?;
+            clipped.label.as_ref().and_then(group)
+        };
+        let group: Option<GroupKey<Clipped<L>>> = self.group.map(|_| &group as GroupKey<_>);
+        for window in windows {
+            let clipped = clip(self.intervals.as_slice(), window);
+            let window_layout = layout(clipped.as_slice(), Some(window), group);
+            let mut rows = vec![vec![Span::raw(header(window))]];
+            for lane in window_layout.lanes() {
+                let blocks = build_blocks(lane.intervals().as_slice(), width, Some(window))
+                    .map_err(|e| e.map_label(|clipped| clipped.and_then(|clipped| clipped.label)))?
+                    .into_iter()
+                    .map(Block::from)
+                    .collect();
+                let (blocks, marks) = unclip(blocks);
+                let mut lines = self.render_lane(blocks.as_slice(), categories);
+                if let Some(first) = lines.first_mut() {
+                    for (column, mark) in marks {
+                        *first = overwrite(first, column, mark);
                     }
-                },
+                }
+                rows.extend(lines);
+            }
+            if window_layout.groups.is_empty() {
+                rows.push(vec![Span::raw(" ".repeat(width))]);
+            }
+            sink(rows)?;
+        }
+        Ok(())
+    }
+
+    fn render_vertical(
+        &self,
+        layout: &Layout<L>,
+        categories: &CategorySets,
+    ) -> Result<Vec<Vec<Line>>, TBLError<L>> {
+        let style = self.segment_style.unwrap_or(SegmentStyle::Plain);
+        let body = style.vertical_body();
+        let columns = layout
+            .lanes()
+            .map(|lane| {
+                let blocks: Vec<Block<L>> =
+                    build_blocks(lane.intervals().as_slice(), self.length, layout.boundaries)?
+                        .into_iter()
+                        .map(Block::from)
+                        .collect();
+                let fills = self.fills(blocks.as_slice(), categories);
+                let column: Vec<Line> = blocks
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, block)| match block {
+                        Block::Space(rows) => {
+                            vec![vec![Span::raw(" ".repeat(self.lane_width))]; *rows]
+                        }
+                        Block::Segment(rows, label) => {
+                            let fill = fills.get(i).and_then(Option::as_ref);
+                            let lines: Vec<Line> = match (self.renderer, fill, self.segment_style) {
+                                // one cell per row, only the first one is labelled
+                                (Some(renderer), None, None) => (0..*rows)
+                                    .map(|row| {
+                                        let label = label.clone().filter(|_| row == 0);
+                                        let cell =
+                                            renderer(&Block::Segment(self.lane_width, label));
+                                        let line = styled_lines(&cell).swap_remove(0);
+                                        fit(&line, self.lane_width)
+                                    })
+                                    .collect(),
+                                _ => {
+                                    let text = label
+                                        .as_ref()
+                                        .and_then(|label| self.label_format.map(|f| f(label)));
+                                    let body = fill.map_or(body, Fill::pattern);
+                                    segment_rows(
+                                        *rows,
+                                        self.lane_width,
+                                        text.as_deref(),
+                                        &|row| style.vertical_row(row, *rows, body),
+                                        fill.is_some_and(Fill::separated),
+                                    )
+                                    .into_iter()
+                                    .map(|row| vec![Span::raw(row)])
+                                    .collect()
+                                }
+                            };
+                            match label
+                                .as_ref()
+                                .and_then(|label| self.label_color(label, categories))
+                            {
+                                Some(color) => {
+                                    lines.iter().map(|line| colorize(line, color)).collect()
+                                }
+                                None => lines,
+                            }
+                        }
+                    })
+                    .collect();
+                Ok(column)
+            })
+            .collect::<Result<Vec<Vec<Line>>, TBLError<L>>>()?;
+        if columns.is_empty() {
+            return Ok(vec![]);
+        }
+        let headers = if layout.groups.iter().any(|group| group.name.is_some()) {
+            Some(
+                layout
+                    .groups
+                    .iter()
+                    .map(|group| (group.name.clone().unwrap_or_default(), group.lanes.len()))
+                    .collect(),
             )
-            .collect::<Result<Vec<Vec<String>>, TBLError<L>>>()?;
-        Ok(rendered)
+        } else {
+            None
+        };
+        Ok(vec![join_columns(
+            columns.as_slice(),
+            self.lane_width,
+            headers,
+        )])
+    }
+
+    /// Layout pass shared by all backends: finite non empty intervals are grouped
+    /// (see `Renderer::with_groups`) then split into non overlapping lanes.
+    pub fn layout(&self) -> Layout<L> {
+        layout(self.intervals.as_slice(), self.boundaries, self.group)
+    }
+
+    /// Render intervals as an SVG document.
+    ///
+    /// Same lanes and scale as `Renderer::render`, one column is 8px wide but positions are not rounded.
+    /// Segments colors are taken from the palette (see `Renderer::with_palette`) or from the
+    /// first styled span rendered by the block renderer.
+    ///
+    /// ```
+    /// use tbl::Renderer;
+    /// let data = vec![(0., 2., "alice"), (1., 3., "bob")];
+    /// let svg = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+    ///     Some(l.to_string())
+    /// })
+    /// .with_length(40)
+    /// .with_groups(&|label| Some(label.to_uppercase()))
+    /// .with_label_format(&|label| label.clone())
+    /// .render_svg()
+    /// .unwrap();
+    /// assert!(svg.starts_with("<svg"));
+    /// assert!(svg.contains(r##"<rect x="10.00" y="30.00" width="213.33" height="20.00" fill="#607d8b"><title>alice</title></rect>"##));
+    /// assert!(svg.contains(r#"font-weight="bold">BOB</text>"#));
+    /// ```
+    pub fn render_svg(&self) -> Result<String, TBLError<L>> {
+        let layout = self.layout();
+        let categories = self.categories();
+        let color = |segment: &Segment<L>| self.segment_color(&layout, segment, &categories);
+        Ok(render_svg(
+            &layout,
+            self.length,
+            self.label_format.unwrap_or(&debug_label),
+            self.bound_format,
+            &color,
+        ))
+    }
+
+    /// Render intervals as a self-contained HTML document (inline CSS, no external assets).
+    ///
+    /// Same lanes and scale as `Renderer::render`, positions are not rounded.
+    /// Hovering a segment shows its label, start, end and duration.
+    /// Group headers and the legend (see `Renderer::with_palette`) are included.
+    ///
+    /// ```
+    /// use tbl::Renderer;
+    /// let data = vec![(0., 2., "alice"), (1., 3., "bob")];
+    /// let html = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+    ///     Some(l.to_string())
+    /// })
+    /// .with_groups(&|label| Some(label.to_uppercase()))
+    /// .with_label_format(&|label| label.clone())
+    /// .with_duration_format(&|d| format!("{}s", d))
+    /// .render_html()
+    /// .unwrap();
+    /// assert!(html.starts_with("<!DOCTYPE html>"));
+    /// assert!(html.contains(r#"<div class="group-header">BOB</div>"#));
+    /// assert!(html.contains(r#"style="left: 33.3333%; width: 66.6667%;"#));
+    /// assert!(html.contains("bob\nstart: 1\nend: 3\nduration: 2s"));
+    /// ```
+    pub fn render_html(&self) -> Result<String, TBLError<L>> {
+        let layout = self.layout();
+        let categories = self.categories();
+        let color = |segment: &Segment<L>| self.segment_color(&layout, segment, &categories);
+        Ok(render_html(
+            &layout,
+            self.label_format.unwrap_or(&debug_label),
+            self.bound_format,
+            self.duration_format,
+            &color,
+            self.legend_entries().as_slice(),
+        ))
+    }
+
+    /// Render intervals as a Markdown report.
+    ///
+    /// The timeline is rendered in a fenced code block without colors nor escape codes,
+    /// followed by a table listing each interval label, start, end, duration, group and lane
+    /// (lanes are numbered in `Renderer::render` order).
+    ///
+    /// ```
+    /// use tbl::Renderer;
+    /// let data = vec![(1., 2., "a|b"), (3., 4., "c")];
+    /// let report = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+    ///     Some(l.to_string())
+    /// })
+    /// .with_length(6)
+    /// .with_label_format(&|label| label.clone())
+    /// .render_markdown()
+    /// .unwrap();
+    /// assert_eq!(
+    ///     report,
+    ///     "```text\n==  ==\n```\n\n\
+    ///      | label | start | end | duration | lane |\n\
+    ///      |---|---|---|---|---|\n\
+    ///      | a\\|b | 1 | 2 | 1 | 1 |\n\
+    ///      | c | 3 | 4 | 1 | 1 |\n"
+    /// );
+    /// ```
+    pub fn render_markdown(&self) -> Result<String, TBLError<L>> {
+        let lines: Vec<String> = self
+            .render_styled()?
+            .iter()
+            .flatten()
+            .map(|line| strip(&plain(line)))
+            .collect();
+        Ok(render_markdown(
+            &self.layout(),
+            lines.as_slice(),
+            self.label_format.unwrap_or(&debug_label),
+            self.bound_format,
+            self.duration_format,
+        ))
+    }
+
+    /// Render intervals as a calendar grid: one cell per day, one row per week (Monday to Sunday).
+    ///
+    /// Bounds are Unix timestamps converted to local days using `time` (see `CalendarTime`).
+    /// Each cell is shaded from `·` (nothing) to `█` according to `shading`, rows are labelled
+    /// using `Renderer::with_bound_format` of their Monday.
+    /// Rendering fails with `TBLError::TooManyRows` beyond 10 000 weeks.
+    ///
+    /// ```
+    /// use tbl::{CalendarTime, Renderer, Shading};
+    /// const DAY: f64 = 86_400.;
+    /// // Monday 1970-01-05 to Wednesday 1970-01-14
+    /// let data = vec![(4. * DAY, 4.5 * DAY), (6. * DAY, 7. * DAY), (13. * DAY, 13.1 * DAY)];
+    /// let calendar = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_bound_format(&|t| format!("day {}", t / DAY))
+    ///     .render_calendar(Shading::Coverage, CalendarTime::default())
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     calendar,
+    ///     vec![
+    ///         "       Mo Tu We Th Fr Sa Su",
+    ///         "day 4  ▒▒ ·· ██ ·· ·· ·· ··",
+    ///         "day 11 ·· ·· ░░            ",
+    ///     ]
+    /// );
+    /// ```
+    pub fn render_calendar(
+        &self,
+        shading: Shading,
+        time: CalendarTime,
+    ) -> Result<Vec<String>, TBLError<L>> {
+        let layout = self.layout();
+        let boundaries = layout.boundaries.ok_or(TBLError::NoBoundaries)?;
+        let bounds: Vec<Bound> = layout
+            .lanes()
+            .flat_map(|lane| lane.segments.iter().map(|segment| segment.bounds))
+            .collect();
+        render_calendar(
+            bounds.as_slice(),
+            boundaries,
+            shading,
+            time,
+            self.bound_format,
+        )
+        .map_err(|weeks| TBLError::TooManyRows(weeks, MAX_WEEKS))
+    }
+
+    fn segment_color(
+        &self,
+        layout: &Layout<L>,
+        segment: &Segment<L>,
+        categories: &CategorySets,
+    ) -> Color {
+        let (a, b) = segment.bounds;
+        let width =
+            layout.position(b, self.length).floor() - layout.position(a, self.length).floor();
+        let block = Block::Segment(width as usize, segment.label.clone());
+        styled_lines(&self.render_block(&block, categories, None))
+            .iter()
+            .flatten()
+            .find_map(|span| span.style.bg.or(span.style.fg))
+            .unwrap_or(DEFAULT_SEGMENT_COLOR)
+    }
+}
+
+/// A label and its style, resolved before lanes are rendered in parallel.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone)]
+struct Styled<L> {
+    label: L,
+    color: Option<Color>,
+    pattern: Option<char>,
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, L> Renderer<'a, L>
+where
+    L: Clone + Debug + Send + Sync,
+{
+    /// Same as `Renderer::render`, lanes are laid out and rendered in parallel (`rayon` feature).
+    ///
+    /// Closures given to the `Renderer` are called on the current thread, only labels must be
+    /// `Send + Sync`: colors and patterns of labels are resolved first. Output order and errors
+    /// are the same as `Renderer::render`. Vertical and wrapped timelines, and timelines
+    /// rendered by a block renderer (see `Renderer::with_renderer`), are not parallelized.
+    ///
+    /// ```
+    /// use tbl::{Bound, Renderer};
+    /// let data: Vec<Bound> = vec![(1., 3.), (2., 4.)];
+    /// let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+    ///     .with_length(6)
+    ///     .render_par()
+    ///     .unwrap();
+    /// assert_eq!(rendered, vec![vec!["  ===="], vec!["====  "]]);
+    /// ```
+    pub fn render_par(&self) -> Result<Vec<Vec<String>>, TBLError<L>> {
+        if self.orientation == Orientation::Vertical
+            || self.wrap.is_some()
+            || self.renderer.is_some()
+        {
+            return self.render();
+        }
+        let categories = self.categories();
+        let color_level = self.color_level.unwrap_or_else(ColorLevel::detect);
+        let intervals: Vec<TBLInterval<Styled<L>>> = self
+            .intervals
+            .iter()
+            .map(|interval| {
+                let label = interval.label.as_ref().map(|label| Styled {
+                    label: label.clone(),
+                    color: self.label_color(label, &categories),
+                    pattern: self.label_pattern(label, &categories),
+                });
+                TBLInterval::new(interval.bounds, label)
+            })
+            .collect();
+        let group = |styled: &Styled<L>| self.group.and_then(|group| group(&styled.label));
+        let group: Option<GroupKey<Styled<L>>> = self.group.map(|_| &group as GroupKey<_>);
+        let grouped = grouped(intervals.as_slice(), self.boundaries, group);
+        let (boundaries, length, segment_style) =
+            (grouped.boundaries, self.length, self.segment_style);
+        let patterned = self.patterns.is_some();
+        let lanes: Vec<Lane<Styled<L>>> = grouped.into_lanes().collect();
+        let rendered: Vec<Result<Vec<String>, TBLError<L>>> = lanes
+            .par_iter()
+            .map(|lane| {
+                let blocks: Vec<Block<Styled<L>>> =
+                    build_blocks(lane.intervals().as_slice(), length, boundaries)
+                        .map_err(|e| e.map_label(|styled| styled.map(|styled| styled.label)))?
+                        .into_iter()
+                        .map(Block::from)
+                        .collect();
+                let pattern = |styled: &Styled<L>| styled.pattern;
+                let lines = lane_lines(
+                    blocks.as_slice(),
+                    segment_style,
+                    None,
+                    Some(&pattern as LabelPattern<_>).filter(|_| patterned),
+                    &|styled| styled.color,
+                );
+                Ok(lines.iter().map(|line| color_level.paint(line)).collect())
+            })
+            .collect();
+        // the first error in lane order
+        rendered.into_iter().collect()
     }
 }
@@ -0,0 +1,261 @@
+//! Calendar grid: one cell per day, weeks are rows and weekdays are columns.
+
+use crate::Bound;
+use itertools::Itertools;
+use std::cmp::Ordering;
+
+/// Seconds in a day.
+pub(crate) const DAY: f64 = 86_400.;
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+/// From an empty day to a fully shaded day.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+/// 1970-01-01 is a Thursday.
+const EPOCH_WEEKDAY: i64 = 3;
+/// Maximum number of weeks (rows) of a calendar.
+pub(crate) const MAX_WEEKS: usize = 10_000;
+
+/// Value of a calendar cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shading {
+    /// Fraction of the day covered by at least one interval.
+    Coverage,
+    /// Total duration of intervals within the day, relative to the busiest day.
+    Intensity,
+}
+
+/// How bounds are converted to local days.
+///
+/// ```
+/// use tbl::CalendarTime;
+/// // bounds in milliseconds, local time is UTC+02:00
+/// let time = CalendarTime::new(1e3, 7200.);
+/// assert_eq!(time.seconds(0.), 7200.);
+/// assert_eq!(time.bound(7200.), 0.);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalendarTime {
+    /// Bound units per second, e.g. `1000.` for Unix timestamps in milliseconds.
+    pub per_second: f64,
+    /// Offset of local time from UTC in seconds, e.g. `3600.` for UTC+01:00.
+    pub utc_offset: f64,
+}
+
+impl CalendarTime {
+    pub fn new(per_second: f64, utc_offset: f64) -> Self {
+        CalendarTime {
+            per_second,
+            utc_offset,
+        }
+    }
+
+    /// Local seconds since the epoch of a bound.
+    pub fn seconds(&self, bound: f64) -> f64 {
+        bound / self.per_second + self.utc_offset
+    }
+
+    /// Bound of local seconds since the epoch.
+    pub fn bound(&self, seconds: f64) -> f64 {
+        (seconds - self.utc_offset) * self.per_second
+    }
+}
+
+impl Default for CalendarTime {
+    /// Unix timestamps in seconds, UTC.
+    fn default() -> Self {
+        CalendarTime::new(1., 0.)
+    }
+}
+
+fn day(timestamp: f64) -> i64 {
+    (timestamp / DAY).floor() as i64
+}
+
+/// Monday of the week of `day`.
+fn monday(day: i64) -> i64 {
+    day - (day + EPOCH_WEEKDAY).rem_euclid(7)
+}
+
+fn merge(bounds: &[Bound]) -> Vec<Bound> {
+    bounds
+        .iter()
+        .sorted_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .fold(vec![], |mut merged: Vec<Bound>, &(a, b)| {
+            match merged.last_mut() {
+                Some(last) if a <= last.1 => last.1 = last.1.max(b),
+                _ => merged.push((a, b)),
+            }
+            merged
+        })
+}
+
+/// Values (0 to 1) of `days` consecutive days starting at `first` (days since epoch).
+pub(crate) fn day_values(bounds: &[Bound], first: i64, days: usize, shading: Shading) -> Vec<f64> {
+    let bounds = match shading {
+        Shading::Coverage => merge(bounds),
+        Shading::Intensity => bounds.to_vec(),
+    };
+    let mut values = vec![0.; days];
+    for (a, b) in bounds {
+        for d in day(a)..=day(b) {
+            let index = d - first;
+            if index < 0 || index >= days as i64 {
+                continue;
+            }
+            let start = d as f64 * DAY;
+            let overlap = b.min(start + DAY) - a.max(start);
+            if overlap > 0. {
+                values[index as usize] += overlap;
+            }
+        }
+    }
+    let max = match shading {
+        Shading::Coverage => DAY,
+        Shading::Intensity => values.iter().cloned().fold(0., f64::max),
+    };
+    if max > 0. {
+        values
+            .iter_mut()
+            .for_each(|value| *value = (*value / max).min(1.));
+    }
+    values
+}
+
+fn shade(value: f64) -> char {
+    if value > 0. {
+        SHADES[((value * 4.).ceil() as usize).clamp(1, 4)]
+    } else {
+        SHADES[0]
+    }
+}
+
+/// Calendar lines: a weekday header then one row per week, labelled by `label` of its Monday.
+///
+/// `bounds` and `boundaries` are converted to local days using `time`, days outside of
+/// `boundaries` are blank. Returns the number of weeks if there would be more than `MAX_WEEKS`.
+pub(crate) fn render_calendar(
+    bounds: &[Bound],
+    boundaries: Bound,
+    shading: Shading,
+    time: CalendarTime,
+    label: &dyn Fn(f64) -> String,
+) -> Result<Vec<String>, usize> {
+    let (min, max) = (time.seconds(boundaries.0), time.seconds(boundaries.1));
+    // checked before days are allocated (and converted to integers)
+    let weeks = ((max - min) / (7. * DAY)).floor() + 2.;
+    if weeks.is_nan() {
+        return Err(usize::MAX);
+    }
+    if weeks > MAX_WEEKS as f64 {
+        // saturates for huge counts
+        return Err(weeks as usize);
+    }
+    let bounds: Vec<Bound> = bounds
+        .iter()
+        .map(|&(a, b)| (time.seconds(a), time.seconds(b)))
+        .collect();
+    let first = day(min);
+    // a timeline ending at midnight does not cover the next day
+    let last = if max > min && max == day(max) as f64 * DAY {
+        day(max) - 1
+    } else {
+        day(max)
+    };
+    let values = day_values(
+        bounds.as_slice(),
+        first,
+        (last - first + 1) as usize,
+        shading,
+    );
+    let weeks: Vec<i64> = (monday(first)..=last).step_by(7).collect();
+    let labels: Vec<String> = weeks
+        .iter()
+        .map(|&w| label(time.bound(w as f64 * DAY)))
+        .collect();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let header = format!("{:width$} {}", "", WEEKDAYS.join(" "), width = label_width);
+    let rows = weeks.iter().zip(labels).map(|(&week, label)| {
+        let cells = (week..week + 7)
+            .map(|d| match values.get((d - first) as usize) {
+                Some(&value) if d >= first => shade(value).to_string().repeat(2),
+                _ => "  ".to_string(),
+            })
+            .join(" ");
+        format!("{:width$} {}", label, cells, width = label_width)
+    });
+    Ok(std::iter::once(header).chain(rows).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calendar::{day_values, monday, render_calendar, CalendarTime, Shading, DAY};
+
+    #[test]
+    fn test_monday() {
+        assert_eq!(monday(0), -3); // 1969-12-29
+        assert_eq!(monday(4), 4); // 1970-01-05
+        assert_eq!(monday(10), 4);
+    }
+
+    #[test]
+    fn test_day_values() {
+        let bounds = vec![(0., DAY / 2.), (DAY / 4., DAY / 2.), (DAY * 1.5, DAY * 2.)];
+        assert_eq!(
+            day_values(bounds.as_slice(), 0, 2, Shading::Coverage),
+            vec![0.5, 0.5]
+        );
+        assert_eq!(
+            day_values(bounds.as_slice(), 0, 2, Shading::Intensity),
+            vec![1., 2. / 3.]
+        );
+    }
+
+    #[test]
+    fn test_render_calendar() {
+        let bounds = vec![(0., DAY), (DAY * 4.25, DAY * 4.5)];
+        let lines = render_calendar(
+            bounds.as_slice(),
+            (0., DAY * 5.),
+            Shading::Coverage,
+            CalendarTime::default(),
+            &|t| format!("{}", t / DAY),
+        )
+        .unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "   Mo Tu We Th Fr Sa Su",
+                "-3          ██ ·· ·· ··",
+                "4  ░░                  ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendar_time() {
+        // milliseconds, UTC-12:00: the first day is 1969-12-31
+        let time = CalendarTime::new(1e3, -12. * 3600.);
+        let lines = render_calendar(
+            &[(0., DAY * 1e3)],
+            (0., DAY * 1e3),
+            Shading::Coverage,
+            time,
+            &|t| format!("{}", t / DAY / 1e3),
+        )
+        .unwrap();
+        assert_eq!(
+            lines,
+            vec!["     Mo Tu We Th Fr Sa Su", "-2.5       ▒▒ ▒▒         "]
+        );
+        // seconds read as milliseconds
+        let result = render_calendar(
+            &[],
+            (0., 1.7e12),
+            Shading::Coverage,
+            CalendarTime::default(),
+            &|t| format!("{}", t),
+        );
+        assert_eq!(result, Err(2_810_848));
+    }
+}
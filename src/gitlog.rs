@@ -0,0 +1,164 @@
+//! `git log` history import: commits, and commit sessions per author.
+
+use crate::Bound;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// `git log --format` of the history read by `parse`: author date (Unix timestamp), author
+/// name and subject, separated by tabs.
+pub const FORMAT: &str = "%at%x09%an%x09%s";
+
+/// A commit, its time is a Unix timestamp (seconds).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub time: f64,
+    pub author: String,
+    pub subject: String,
+}
+
+/// Consecutive commits of an author.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub author: String,
+    /// First and last commit times, equal for single commit sessions.
+    pub bounds: Bound,
+    pub commits: usize,
+}
+
+#[derive(Error, Debug)]
+pub enum GitLogError {
+    /// Lines are numbered from 1.
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+/// Commits of `git log --format=...` output (see `FORMAT`) sorted by time, empty lines are
+/// ignored.
+///
+/// ```
+/// use tbl::gitlog;
+///
+/// // git log --format='%at%x09%an%x09%s'
+/// let log = "\
+/// 1714564800\tAda\tFix parser
+/// 1714561200\tAda\tAdd parser
+/// 1714557600\tGrace\tInitial commit
+/// ";
+/// let commits = gitlog::parse(log)?;
+/// assert_eq!(commits[0].subject, "Initial commit");
+/// let sessions = gitlog::sessions(&commits, 7200.);
+/// assert_eq!(sessions.len(), 2);
+/// assert_eq!(sessions[0].author, "Grace");
+/// assert_eq!((sessions[1].bounds, sessions[1].commits), ((1714561200., 1714564800.), 2));
+/// # Ok::<(), tbl::gitlog::GitLogError>(())
+/// ```
+pub fn parse(text: &str) -> Result<Vec<Commit>, GitLogError> {
+    let mut commits = vec![];
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let error = |message: String| GitLogError::Parse {
+            line: i + 1,
+            message,
+        };
+        let mut fields = line.splitn(3, '\t');
+        let time = fields.next().unwrap_or_default().trim();
+        let time = time
+            .parse::<i64>()
+            .map_err(|_| error(format!("`{}` is not a Unix timestamp", time)))?;
+        let author = fields
+            .next()
+            .ok_or_else(|| error(format!("missing author, expected `{}` lines", FORMAT)))?;
+        commits.push(Commit {
+            time: time as f64,
+            author: author.to_string(),
+            subject: fields.next().unwrap_or_default().to_string(),
+        });
+    }
+    commits.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(commits)
+}
+
+/// Sessions of `commits` sorted by start: consecutive commits of an author closer than `gap`
+/// seconds are merged.
+pub fn sessions(commits: &[Commit], gap: f64) -> Vec<Session> {
+    let mut commits = commits.to_vec();
+    commits.sort_by(|a, b| a.time.total_cmp(&b.time));
+    let mut sessions: Vec<Session> = vec![];
+    // index of the last session by author
+    let mut last: HashMap<&str, usize> = HashMap::new();
+    for commit in commits.iter() {
+        match last.get(commit.author.as_str()) {
+            Some(&index) if commit.time - sessions[index].bounds.1 < gap => {
+                sessions[index].bounds.1 = commit.time;
+                sessions[index].commits += 1;
+            }
+            _ => {
+                last.insert(&commit.author, sessions.len());
+                sessions.push(Session {
+                    author: commit.author.clone(),
+                    bounds: (commit.time, commit.time),
+                    commits: 1,
+                });
+            }
+        }
+    }
+    sessions
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gitlog::{self, Session};
+
+    #[test]
+    fn test_sessions() {
+        let log = "100\tAda\tc\n\n0\tAda\ta\n50\tGrace\tb\n3700\tAda\td\n";
+        let commits = gitlog::parse(log).unwrap();
+        let sessions = gitlog::sessions(&commits, 3600.);
+        // Grace's commit does not split Ada's session, 3700 is one hour after 100
+        assert_eq!(
+            sessions,
+            vec![
+                Session {
+                    author: "Ada".to_string(),
+                    bounds: (0., 100.),
+                    commits: 2
+                },
+                Session {
+                    author: "Grace".to_string(),
+                    bounds: (50., 50.),
+                    commits: 1
+                },
+                Session {
+                    author: "Ada".to_string(),
+                    bounds: (3700., 3700.),
+                    commits: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        // subjects keep their tabs and may be empty
+        let commits = gitlog::parse("0\tAda\ta\tb\n1\tAda\n").unwrap();
+        assert_eq!(commits[0].subject, "a\tb");
+        assert_eq!(commits[1].subject, "");
+        // `git log --oneline`
+        let error = gitlog::parse("abc1234 Fix\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1: `abc1234 Fix` is not a Unix timestamp"
+        );
+        for time in ["inf", "NaN", "1.5"].iter() {
+            let error = gitlog::parse(&format!("0\tAda\ta\n{}\tAda\tb\n", time)).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("line 2: `{}` is not a Unix timestamp", time)
+            );
+        }
+        let error = gitlog::parse("0\n").unwrap_err();
+        assert!(error.to_string().starts_with("line 1: missing author"));
+    }
+}
@@ -0,0 +1,111 @@
+//! Self-contained HTML backend.
+
+use crate::layout::{Layout, Segment};
+use crate::palette::LegendEntry;
+use crate::style::Color;
+use crate::svg::{escape, hex, text_color, ticks};
+use std::fmt::Write;
+
+const STYLE: &str = "\
+body { font-family: monospace; font-size: 12px; margin: 10px; }
+.group-header { font-weight: bold; margin: 8px 0 4px 0; }
+.lane { position: relative; height: 20px; margin-bottom: 4px; }
+.segment { position: absolute; top: 0; height: 100%; box-sizing: border-box; \
+border-right: 1px solid white; white-space: nowrap; }
+.segment .label { display: block; overflow: hidden; text-overflow: ellipsis; \
+padding: 0 2px; line-height: 20px; }
+.segment .tooltip { display: none; position: absolute; top: 22px; left: 0; z-index: 1; \
+padding: 4px; background: #212121; color: white; white-space: pre; }
+.segment:hover .tooltip { display: block; }
+.axis { position: relative; height: 20px; border-top: 1px solid black; }
+.tick { position: absolute; top: 0; transform: translateX(-50%); padding-top: 2px; }
+.legend { margin-top: 12px; }
+.swatch { display: inline-block; width: 2em; margin-right: 4px; text-align: center; }
+";
+
+/// Percentage of the timeline width, not rounded.
+fn percent<L>(layout: &Layout<L>, x: f64) -> f64 {
+    layout.position(x, 100)
+}
+
+pub(crate) fn render_html<L>(
+    layout: &Layout<L>,
+    label: &dyn Fn(&L) -> String,
+    bound: &dyn Fn(f64) -> String,
+    duration: &dyn Fn(f64) -> String,
+    color: &dyn Fn(&Segment<L>) -> Color,
+    legend: &[LegendEntry],
+) -> String {
+    let mut body = String::new();
+    for group in layout.groups.iter() {
+        body.push_str("<div class=\"group\">\n");
+        if let Some(name) = &group.name {
+            let _ = writeln!(body, "<div class=\"group-header\">{}</div>", escape(name));
+        }
+        for lane in group.lanes.iter() {
+            body.push_str("<div class=\"lane\">\n");
+            for segment in lane.segments.iter() {
+                let (a, b) = segment.bounds;
+                let (left, right) = (percent(layout, a), percent(layout, b));
+                let text = segment.label.as_ref().map(label).unwrap_or_default();
+                let fill = color(segment);
+                let tooltip = format!(
+                    "{}\nstart: {}\nend: {}\nduration: {}",
+                    text,
+                    bound(a),
+                    bound(b),
+                    duration(b - a)
+                );
+                let _ = writeln!(
+                    body,
+                    "<div class=\"segment\" style=\"left: {:.4}%; width: {:.4}%; background: {}; color: {};\"><span class=\"label\">{}</span><span class=\"tooltip\">{}</span></div>",
+                    left,
+                    right - left,
+                    hex(fill),
+                    hex(text_color(fill)),
+                    escape(&text),
+                    escape(&tooltip)
+                );
+            }
+            body.push_str("</div>\n");
+        }
+        body.push_str("</div>\n");
+    }
+    body.push_str("<div class=\"axis\">\n");
+    if let Some(boundaries) = layout.boundaries {
+        for tick in ticks(boundaries) {
+            let _ = writeln!(
+                body,
+                "<span class=\"tick\" style=\"left: {:.4}%;\">{}</span>",
+                percent(layout, tick),
+                escape(&bound(tick))
+            );
+        }
+    }
+    body.push_str("</div>\n");
+    if !legend.is_empty() {
+        body.push_str("<div class=\"legend\">\n");
+        for entry in legend {
+            let background = entry
+                .color
+                .map(|color| format!(" style=\"background: {};\"", hex(color)))
+                .unwrap_or_default();
+            let pattern = entry
+                .pattern
+                .map(|pattern| pattern.to_string().repeat(2))
+                .unwrap_or_default();
+            let _ = writeln!(
+                body,
+                "<div><span class=\"swatch\"{}>{}</span>{}</div>",
+                background,
+                escape(&pattern),
+                escape(&entry.key)
+            );
+        }
+        body.push_str("</div>\n");
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>timeline</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        STYLE, body
+    )
+}
@@ -0,0 +1,941 @@
+//! iCalendar (`.ics`) import.
+//!
+//! `VEVENT`s become intervals in seconds since the Unix epoch, like `Renderer::render_calendar`.
+
+use crate::Bound;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+use thiserror::Error;
+
+/// Occurrences of a recurring event imported at most.
+const MAX_OCCURRENCES: usize = 1000;
+
+/// An event read from a calendar, one per occurrence of recurring events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    /// `SUMMARY`
+    pub summary: String,
+    /// Calendar name (`X-WR-CALNAME`), if any.
+    pub calendar: Option<String>,
+    /// `ATTENDEE` common names, or addresses.
+    pub attendees: Vec<String>,
+    /// Events with dates (not date-times) start and end at midnight UTC.
+    pub all_day: bool,
+    /// Start and end, in seconds since the Unix epoch.
+    pub bounds: Bound,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum IcsError {
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+/// A content line: `NAME;PARAM=VALUE:value`.
+struct Property<'t> {
+    line: usize,
+    name: String,
+    params: Vec<(String, &'t str)>,
+    value: &'t str,
+}
+
+impl Property<'_> {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.trim_matches('"'))
+    }
+
+    fn error(&self, message: String) -> IcsError {
+        IcsError::Parse {
+            line: self.line,
+            message,
+        }
+    }
+}
+
+/// Unfolded lines (continuation lines start with a space or a tab) and their line numbers.
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        match (
+            line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')),
+            lines.last_mut(),
+        ) {
+            (Some(continuation), Some((_, last))) => last.push_str(continuation),
+            _ => lines.push((i + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+fn property(line: usize, text: &str) -> Option<Property<'_>> {
+    // the value follows the first `:` outside quotes
+    let mut quoted = false;
+    let colon = text.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let mut parts = text[..colon].split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            Some((name.trim().to_uppercase(), value))
+        })
+        .collect();
+    Some(Property {
+        line,
+        name,
+        params,
+        value: text[colon + 1..].trim(),
+    })
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push(' '),
+            Some(c @ ('\\' | ',' | ';')) => unescaped.push(c),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+fn weekday(day: &str) -> Option<Weekday> {
+    match day {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// `n`-th `weekday` of a month, from the end of the month if `n` is negative.
+fn nth_weekday(year: i32, month: u32, n: i8, weekday: Weekday) -> Option<NaiveDate> {
+    if n > 0 {
+        return NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8);
+    }
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+    let last = next?.pred_opt()?;
+    let back = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    last.checked_sub_signed(Duration::days(i64::from(back) + 7 * (i64::from(-n) - 1)))
+        .filter(|date| date.month() == month)
+}
+
+/// A `STANDARD` or `DAYLIGHT` observance of a `VTIMEZONE`.
+#[derive(Debug, Clone, PartialEq)]
+struct Observance {
+    /// `TZOFFSETTO`, in seconds.
+    offset: i64,
+    /// First onset (`DTSTART`), in local time.
+    start: NaiveDateTime,
+    /// Yearly onsets (`RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU`): month and `n`-th weekday,
+    /// from the end of the month if `n` is negative.
+    yearly: Option<(u32, i8, Weekday)>,
+    until: Option<NaiveDateTime>,
+}
+
+impl Observance {
+    /// Observances with other rules only start at `DTSTART`.
+    fn parse(properties: &[Property], line: usize) -> Result<Observance, IcsError> {
+        let get = |name: &str| {
+            properties
+                .iter()
+                .find(|property| property.name == name)
+                .ok_or_else(|| IcsError::Parse {
+                    line,
+                    message: format!("time zone observance without {}", name),
+                })
+        };
+        let offset = get("TZOFFSETTO")?;
+        let invalid = || offset.error(format!("`{}` is not a UTC offset", offset.value));
+        let (sign, digits) = match (offset.value.get(..1), offset.value.get(1..)) {
+            (Some("+"), Some(digits)) => (1, digits),
+            (Some("-"), Some(digits)) => (-1, digits),
+            _ => return Err(invalid()),
+        };
+        if !(digits.len() == 4 || digits.len() == 6) || !digits.bytes().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        // hours, minutes and optional seconds
+        let part = |i: usize| {
+            digits
+                .get(i..i + 2)
+                .map_or(0, |part| part.parse().unwrap_or(0))
+        };
+        let offset: i64 = sign * (part(0) * 3600 + part(2) * 60 + part(4));
+        let start = get("DTSTART")?;
+        let local = |value: &str| {
+            NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()
+        };
+        let start = local(start.value)
+            .ok_or_else(|| start.error(format!("`{}` is not a date", start.value)))?;
+        let parts: HashMap<&str, &str> = get("RRULE")
+            .map(|rule| {
+                rule.value
+                    .split(';')
+                    .filter_map(|part| part.split_once('='))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let month = parts.get("BYMONTH").and_then(|month| month.parse().ok());
+        let day = parts.get("BYDAY").and_then(|day| {
+            let at = day.len().checked_sub(2)?;
+            let (n, day) = (day.get(..at)?, day.get(at..)?);
+            Some((n.parse::<i8>().ok()?, weekday(day)?))
+        });
+        let yearly = match (parts.get("FREQ"), month, day) {
+            (Some(&"YEARLY"), Some(month), Some((n, day))) if n != 0 && (-5..=5).contains(&n) => {
+                Some((month, n, day))
+            }
+            _ => None,
+        };
+        Ok(Observance {
+            offset,
+            start,
+            yearly,
+            until: parts.get("UNTIL").and_then(|until| local(until)),
+        })
+    }
+
+    /// Latest onset at or before `local`.
+    fn onset(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        let onsets = match self.yearly {
+            None => vec![self.start],
+            Some((month, n, day)) => [local.year(), local.year() - 1]
+                .iter()
+                .filter_map(|&year| nth_weekday(year, month, n, day))
+                .map(|date| date.and_time(self.start.time()))
+                .filter(|&onset| self.until.map_or(true, |until| onset <= until))
+                .collect(),
+        };
+        onsets
+            .into_iter()
+            .filter(|&onset| self.start <= onset && onset <= local)
+            .max()
+    }
+}
+
+/// `VTIMEZONE` definitions of a calendar by `TZID`.
+type TimeZones = HashMap<String, Rc<[Observance]>>;
+
+fn time_zones(lines: &[(usize, String)]) -> Result<TimeZones, IcsError> {
+    let mut zones = TimeZones::new();
+    let mut zone: Option<(String, Vec<Observance>)> = None;
+    // the line of its `BEGIN` and its properties
+    let mut observance: Option<(usize, Vec<Property>)> = None;
+    for (line, text) in lines.iter() {
+        let property = match property(*line, text) {
+            Some(property) => property,
+            None => continue,
+        };
+        match (property.name.as_str(), property.value) {
+            ("BEGIN", "VTIMEZONE") => zone = Some((String::new(), vec![])),
+            ("END", "VTIMEZONE") => {
+                if let Some((tzid, observances)) = zone.take() {
+                    if !observances.is_empty() {
+                        zones.insert(tzid, observances.into());
+                    }
+                }
+            }
+            ("BEGIN", "STANDARD") | ("BEGIN", "DAYLIGHT") if zone.is_some() => {
+                observance = Some((*line, vec![]))
+            }
+            ("END", "STANDARD") | ("END", "DAYLIGHT") => {
+                if let (Some((begin, properties)), Some((_, observances))) =
+                    (observance.take(), &mut zone)
+                {
+                    observances.push(Observance::parse(&properties, begin)?);
+                }
+            }
+            _ => match (&mut observance, &mut zone) {
+                (Some((_, properties)), _) => properties.push(property),
+                (None, Some((tzid, _))) if property.name == "TZID" => {
+                    *tzid = property.value.to_string()
+                }
+                _ => {}
+            },
+        }
+    }
+    Ok(zones)
+}
+
+/// Time zone of a date-time.
+#[derive(Debug, Clone, PartialEq)]
+enum Zone {
+    Utc,
+    /// No zone (floating): UTC.
+    Floating,
+    Tz(Tz),
+    /// A `VTIMEZONE` of the calendar.
+    Defined(Rc<[Observance]>),
+}
+
+impl Zone {
+    fn seconds(&self, datetime: NaiveDateTime) -> f64 {
+        let utc = match self {
+            Zone::Utc | Zone::Floating => datetime.and_utc(),
+            // skipped local times (DST gaps) are shifted by an hour
+            Zone::Tz(tz) => tz
+                .from_local_datetime(&datetime)
+                .earliest()
+                .or_else(|| {
+                    datetime
+                        .checked_add_signed(Duration::hours(1))
+                        .and_then(|datetime| tz.from_local_datetime(&datetime).earliest())
+                })
+                .map_or_else(|| datetime.and_utc(), |local| local.with_timezone(&Utc)),
+            Zone::Defined(observances) => {
+                // before all onsets: the offset of the first observance
+                let offset = observances
+                    .iter()
+                    .filter_map(|observance| Some((observance.onset(datetime)?, observance.offset)))
+                    .max_by_key(|&(onset, _)| onset)
+                    .or_else(|| {
+                        observances
+                            .iter()
+                            .map(|observance| (observance.start, observance.offset))
+                            .min_by_key(|&(start, _)| start)
+                    })
+                    .map_or(0, |(_, offset)| offset);
+                return (datetime.and_utc().timestamp() - offset) as f64;
+            }
+        };
+        utc.timestamp() as f64
+    }
+}
+
+/// A `DATE` or `DATE-TIME` value.
+#[derive(Debug, Clone, PartialEq)]
+struct Time {
+    datetime: NaiveDateTime,
+    zone: Zone,
+    all_day: bool,
+}
+
+impl Time {
+    /// `TZID`s are IANA time zones or defined in `zones`.
+    fn parse(property: &Property, zones: &TimeZones) -> Result<Time, IcsError> {
+        let value = property.value;
+        let invalid = || property.error(format!("`{}` is not a date", value));
+        if property.param("VALUE") == Some("DATE") || !value.contains('T') {
+            let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+            return Ok(Time {
+                datetime: date.and_time(NaiveTime::MIN),
+                zone: Zone::Floating,
+                all_day: true,
+            });
+        }
+        let (value, utc) = match value.strip_suffix('Z') {
+            Some(value) => (value, true),
+            None => (value, false),
+        };
+        let datetime =
+            NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        let zone = match (utc, property.param("TZID")) {
+            (true, _) => Zone::Utc,
+            (false, Some(tzid)) => match tzid.trim_start_matches('/').parse() {
+                Ok(tz) => Zone::Tz(tz),
+                Err(_) => zones
+                    .get(tzid)
+                    .map(|observances| Zone::Defined(observances.clone()))
+                    .ok_or_else(|| property.error(format!("unknown time zone `{}`", tzid)))?,
+            },
+            (false, None) => Zone::Floating,
+        };
+        Ok(Time {
+            datetime,
+            zone,
+            all_day: false,
+        })
+    }
+
+    fn seconds(&self) -> f64 {
+        self.zone.seconds(self.datetime)
+    }
+}
+
+/// `P1W`, `PT1H30M`, `P1DT12H`... in seconds.
+fn duration(property: &Property) -> Result<f64, IcsError> {
+    let invalid = || property.error(format!("`{}` is not a duration", property.value));
+    let (sign, value) = match property.value.strip_prefix('-') {
+        Some(value) => (-1., value),
+        None => (1., property.value.trim_start_matches('+')),
+    };
+    let value = value.strip_prefix('P').ok_or_else(invalid)?;
+    let mut seconds = 0.;
+    let mut number = String::new();
+    let mut time = false;
+    for c in value.chars() {
+        let unit = match (c, time) {
+            ('T', _) => {
+                time = true;
+                continue;
+            }
+            (c, _) if c.is_ascii_digit() => {
+                number.push(c);
+                continue;
+            }
+            ('W', false) => 604_800.,
+            ('D', false) => 86_400.,
+            ('H', true) => 3_600.,
+            ('M', true) => 60.,
+            ('S', true) => 1.,
+            _ => return Err(invalid()),
+        };
+        seconds += number.parse::<f64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+    Ok(sign * seconds)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A simple `RRULE`: `FREQ`, `INTERVAL`, `COUNT`, `UNTIL` and `BYDAY` (weekly rules).
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    /// In seconds.
+    until: Option<f64>,
+    weekdays: Vec<Weekday>,
+}
+
+impl Rule {
+    /// `None` for rules with other parts, only their first occurrence is imported.
+    fn parse(property: &Property, zones: &TimeZones) -> Result<Option<Rule>, IcsError> {
+        let invalid = || property.error(format!("`{}` is not a recurrence rule", property.value));
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            weekdays: vec![],
+        };
+        let mut frequency = None;
+        for part in property.value.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(invalid)?;
+            match name {
+                "FREQ" => {
+                    frequency = match value {
+                        "DAILY" => Some(Frequency::Daily),
+                        "WEEKLY" => Some(Frequency::Weekly),
+                        "MONTHLY" => Some(Frequency::Monthly),
+                        "YEARLY" => Some(Frequency::Yearly),
+                        _ => return Ok(None),
+                    }
+                }
+                "INTERVAL" => rule.interval = value.parse().map_err(|_| invalid())?,
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| invalid())?),
+                "UNTIL" => {
+                    let until = Property {
+                        line: property.line,
+                        name: "UNTIL".to_string(),
+                        params: vec![],
+                        value,
+                    };
+                    rule.until = Some(Time::parse(&until, zones)?.seconds());
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        match weekday(day) {
+                            Some(weekday) => rule.weekdays.push(weekday),
+                            // e.g. `1MO`
+                            None => return Ok(None),
+                        }
+                    }
+                }
+                "WKST" => {}
+                _ => return Ok(None),
+            }
+        }
+        rule.frequency = frequency.ok_or_else(invalid)?;
+        if rule.interval == 0 || (!rule.weekdays.is_empty() && rule.frequency != Frequency::Weekly)
+        {
+            return Ok(None);
+        }
+        rule.weekdays.sort_by_key(Weekday::num_days_from_monday);
+        Ok(Some(rule))
+    }
+
+    /// Starts of the `period`-th period, none for skipped dates (e.g. February 30th), `None` once
+    /// dates are out of range.
+    fn starts(&self, start: NaiveDateTime, period: u32) -> Option<Vec<NaiveDateTime>> {
+        let step = period.checked_mul(self.interval)?;
+        let date = start.date();
+        let dates = match self.frequency {
+            Frequency::Daily => vec![date.checked_add_signed(Duration::days(i64::from(step)))?],
+            Frequency::Weekly => {
+                let weekday = i64::from(date.weekday().num_days_from_monday());
+                let monday = date
+                    .checked_sub_signed(Duration::days(weekday))?
+                    .checked_add_signed(Duration::weeks(i64::from(step)))?;
+                if self.weekdays.is_empty() {
+                    vec![monday.checked_add_signed(Duration::days(weekday))?]
+                } else {
+                    self.weekdays
+                        .iter()
+                        .map(|day| {
+                            monday.checked_add_signed(Duration::days(i64::from(
+                                day.num_days_from_monday(),
+                            )))
+                        })
+                        .collect::<Option<_>>()?
+                }
+            }
+            Frequency::Monthly => {
+                let month = date.month0().checked_add(step)?;
+                let year = date.year().checked_add(i32::try_from(month / 12).ok()?)?;
+                if year > NaiveDate::MAX.year() {
+                    return None;
+                }
+                NaiveDate::from_ymd_opt(year, month % 12 + 1, date.day())
+                    .into_iter()
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = date.year().checked_add(i32::try_from(step).ok()?)?;
+                if year > NaiveDate::MAX.year() {
+                    return None;
+                }
+                NaiveDate::from_ymd_opt(year, date.month(), date.day())
+                    .into_iter()
+                    .collect()
+            }
+        };
+        Some(
+            dates
+                .into_iter()
+                .map(|date| date.and_time(start.time()))
+                .filter(|&occurrence| occurrence >= start)
+                .collect(),
+        )
+    }
+}
+
+#[derive(Default)]
+struct Event<'t> {
+    properties: Vec<Property<'t>>,
+}
+
+impl Event<'_> {
+    fn get(&self, name: &str) -> Option<&Property<'_>> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    fn all(&self, name: &str) -> impl Iterator<Item = &Property<'_>> {
+        let name = name.to_string();
+        self.properties
+            .iter()
+            .filter(move |property| property.name == name)
+    }
+
+    /// Occurrences of the event intersecting `window`.
+    fn occurrences(
+        &self,
+        calendar: &Option<String>,
+        zones: &TimeZones,
+        window: Option<Bound>,
+        line: usize,
+    ) -> Result<Vec<IcsEvent>, IcsError> {
+        let start = self.get("DTSTART").ok_or(IcsError::Parse {
+            line,
+            message: "event without DTSTART".to_string(),
+        })?;
+        let start = Time::parse(start, zones)?;
+        let length = match (self.get("DTEND"), self.get("DURATION")) {
+            (Some(end), _) => Time::parse(end, zones)?.seconds() - start.seconds(),
+            (None, Some(length)) => duration(length)?,
+            (None, None) if start.all_day => 86_400.,
+            (None, None) => 0.,
+        };
+        let excluded: Vec<f64> = self
+            .all("EXDATE")
+            .flat_map(|property| {
+                property.value.split(',').map(move |value| {
+                    let exdate = Property {
+                        line: property.line,
+                        name: "EXDATE".to_string(),
+                        params: property.params.clone(),
+                        value,
+                    };
+                    Time::parse(&exdate, zones).map(|time| time.seconds())
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let rule = match self.get("RRULE") {
+            Some(rule) => Rule::parse(rule, zones)?,
+            None => None,
+        };
+
+        let mut starts = vec![];
+        match &rule {
+            None => starts.push(start.seconds()),
+            Some(rule) => {
+                let unbounded = rule.count.is_none() && rule.until.is_none() && window.is_none();
+                let mut count = 0;
+                // until dates are out of range, if there is no other limit
+                'periods: for period in 0.. {
+                    let occurrences = match rule.starts(start.datetime, period) {
+                        Some(occurrences) => occurrences,
+                        None => break,
+                    };
+                    for occurrence in occurrences {
+                        let seconds = start.zone.seconds(occurrence);
+                        let past_window = window.is_some_and(|(_, end)| seconds > end);
+                        if rule.count.is_some_and(|max| count >= max)
+                            || rule.until.is_some_and(|until| seconds > until)
+                            || past_window
+                        {
+                            break 'periods;
+                        }
+                        count += 1;
+                        if window.is_some_and(|(start, _)| seconds + length < start) {
+                            continue;
+                        }
+                        if starts.len() == MAX_OCCURRENCES {
+                            if unbounded {
+                                break 'periods;
+                            }
+                            return Err(IcsError::Parse {
+                                line,
+                                message: format!(
+                                    "recurring event with more than {} occurrences",
+                                    MAX_OCCURRENCES
+                                ),
+                            });
+                        }
+                        starts.push(seconds);
+                    }
+                }
+            }
+        }
+
+        let summary = self
+            .get("SUMMARY")
+            .map(|summary| unescape(summary.value))
+            .unwrap_or_default();
+        let attendees: Vec<String> = self
+            .all("ATTENDEE")
+            .map(|attendee| {
+                let address = attendee.value;
+                let address = address
+                    .strip_prefix("mailto:")
+                    .or_else(|| address.strip_prefix("MAILTO:"))
+                    .unwrap_or(address);
+                attendee.param("CN").unwrap_or(address).to_string()
+            })
+            .collect();
+        Ok(starts
+            .into_iter()
+            .filter(|start| !excluded.contains(start))
+            .map(|start| (start, start + length))
+            .filter(|&(start, end)| window.map_or(true, |(min, max)| end >= min && start <= max))
+            .map(|bounds| IcsEvent {
+                summary: summary.clone(),
+                calendar: calendar.clone(),
+                attendees: attendees.clone(),
+                all_day: start.all_day,
+                bounds,
+            })
+            .collect())
+    }
+}
+
+/// Events of an iCalendar calendar intersecting `window` (all events if `None`), sorted by start.
+///
+/// Events end at `DTEND` or after `DURATION`, all-day events last one day by default.
+/// Date-times are converted from their `TZID`: an IANA time zone, or a `VTIMEZONE` of the
+/// calendar (e.g. Windows time zone names) whose observances start at `DTSTART` or follow
+/// yearly `BYMONTH` and `BYDAY` rules. Floating date-times are UTC, unknown time zones are an
+/// error.
+///
+/// Recurring events are expanded (`EXDATE`s are excluded) for simple `RRULE`s:
+/// `FREQ`, `INTERVAL`, `COUNT`, `UNTIL` and `BYDAY` without ordinals for weekly rules,
+/// only the first occurrence of events with other rules is imported.
+/// At most 1000 occurrences of an event are imported: the first ones of rules without `COUNT`,
+/// `UNTIL` nor window, more occurrences (in the window) are an error otherwise.
+/// Occurrences after year 262142 are never imported.
+///
+/// ```
+/// use tbl::ics;
+/// let events = ics::parse("BEGIN:VCALENDAR
+/// X-WR-CALNAME:Team
+/// BEGIN:VEVENT
+/// SUMMARY:Standup
+/// DTSTART;TZID=Europe/Paris:20240506T093000
+/// DURATION:PT15M
+/// RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=3
+/// ATTENDEE;CN=Alice:mailto:alice@example.com
+/// END:VEVENT
+/// END:VCALENDAR
+/// ", None).unwrap();
+/// assert_eq!(events.len(), 3);
+/// assert_eq!(events[0].bounds, (1_714_980_600., 1_714_981_500.)); // 2024-05-06T07:30:00Z
+/// assert_eq!(events[1].bounds.0 - events[0].bounds.0, 2. * 86_400.);
+/// assert_eq!(events[0].calendar.as_deref(), Some("Team"));
+/// assert_eq!(events[0].attendees, vec!["Alice"]);
+/// ```
+pub fn parse(text: &str, window: Option<Bound>) -> Result<Vec<IcsEvent>, IcsError> {
+    let lines = unfold(text);
+    let zones = time_zones(&lines)?;
+    let mut calendar = None;
+    let mut event: Option<(usize, Event)> = None;
+    let mut events = vec![];
+    // components nested in events (e.g. `VALARM`)
+    let mut nested = vec![];
+    for (line, text) in lines.iter() {
+        let property = match property(*line, text) {
+            Some(property) => property,
+            None => continue,
+        };
+        match (property.name.as_str(), property.value, &mut event) {
+            ("BEGIN", "VEVENT", None) => {
+                event = Some((*line, Event::default()));
+                nested.clear();
+            }
+            ("END", name, Some(_)) if nested.last() == Some(&name) => {
+                nested.pop();
+            }
+            ("END", "VEVENT", Some(_)) => {
+                if let Some((line, event)) = event.take() {
+                    events.extend(event.occurrences(&calendar, &zones, window, line)?);
+                }
+            }
+            ("BEGIN", name, Some(_)) => nested.push(name),
+            // stray `END`s
+            ("END", _, Some(_)) => {}
+            (_, _, Some((_, event))) if nested.is_empty() => event.properties.push(property),
+            ("X-WR-CALNAME", name, None) => calendar = Some(unescape(name)),
+            _ => {}
+        }
+    }
+    events.sort_by(|a, b| a.bounds.0.total_cmp(&b.bounds.0));
+    Ok(events)
+}
+
+/// One event per attendee (events without attendees are kept), e.g. to group events by
+/// attendee using `|e| e.attendees.first().cloned()`.
+pub fn by_attendee(events: Vec<IcsEvent>) -> Vec<IcsEvent> {
+    events
+        .into_iter()
+        .flat_map(|event| {
+            if event.attendees.is_empty() {
+                vec![event]
+            } else {
+                event
+                    .attendees
+                    .iter()
+                    .map(|attendee| IcsEvent {
+                        attendees: vec![attendee.clone()],
+                        ..event.clone()
+                    })
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ics::{by_attendee, parse, IcsError};
+
+    const DAY: f64 = 86_400.;
+    // 2024-05-01T00:00:00Z
+    const MAY: f64 = 1_714_521_600.;
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\n{}END:VCALENDAR\r\n", events)
+    }
+
+    #[test]
+    fn test_all_day_and_folding() {
+        let text = calendar(
+            "BEGIN:VEVENT\r\nSUMMARY:Long\r\n  weekend\\, off\r\nDTSTART;VALUE=DATE:20240504\r\n\
+             DTEND;VALUE=DATE:20240506\r\nBEGIN:VALARM\r\nSUMMARY:alarm\r\nEND:VALARM\r\n\
+             END:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20240501\r\nEND:VEVENT\r\n",
+        );
+        let events = parse(&text, None).unwrap();
+        assert_eq!(events[0].bounds, (MAY, MAY + DAY));
+        assert_eq!(events[1].summary, "Long weekend, off");
+        assert!(events[1].all_day);
+        assert_eq!(events[1].bounds, (MAY + 3. * DAY, MAY + 5. * DAY));
+
+        // an escaped backslash followed by `n`, and a stray `END` in an event
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART:20240501\r\nEND:VALARM\r\nBEGIN:VALARM\r\n\
+             SUMMARY:alarm\r\nEND:VALARM\r\nSUMMARY:C:\\\\new\\nline\r\nEND:VEVENT\r\n",
+        );
+        let events = parse(&text, None).unwrap();
+        assert_eq!(events[0].summary, "C:\\new line");
+    }
+
+    #[test]
+    fn test_recurrences() {
+        let text = calendar(
+            "BEGIN:VEVENT\r\nSUMMARY:Daily\r\nDTSTART:20240501T100000Z\r\nDTEND:20240501T110000Z\r\n\
+             RRULE:FREQ=DAILY;INTERVAL=2\r\nEXDATE:20240503T100000Z\r\n\
+             ATTENDEE;CN=\"Bob: ops\":mailto:bob@example.com\r\nATTENDEE:mailto:carol@example.com\r\n\
+             END:VEVENT\r\nBEGIN:VEVENT\r\nSUMMARY:Monthly\r\nDTSTART:20240131T100000Z\r\n\
+             RRULE:FREQ=MONTHLY;UNTIL=20240601T000000Z\r\nEND:VEVENT\r\n",
+        );
+        let window = Some((MAY, MAY + 6. * DAY));
+        let events = parse(&text, window).unwrap();
+        let daily: Vec<f64> = events
+            .iter()
+            .filter(|e| e.summary == "Daily")
+            .map(|e| (e.bounds.0 - MAY) / 3600.)
+            .collect();
+        // every other day from May 1st at 10:00, May 3rd excluded
+        assert_eq!(daily, vec![10., 106.]);
+        assert_eq!(events[0].attendees, vec!["Bob: ops", "carol@example.com"]);
+        assert_eq!(by_attendee(events).len(), 4);
+
+        let events = parse(&text, None).unwrap();
+        let monthly = events.iter().filter(|e| e.summary == "Monthly").count();
+        // January, March and May 31st
+        assert_eq!(monthly, 3);
+        assert_eq!(events.len(), 1000 - 1 + 3);
+    }
+
+    #[test]
+    fn test_too_many_occurrences() {
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART:20240501T100000Z\r\nRRULE:FREQ=DAILY;COUNT=2000\r\n\
+             END:VEVENT\r\n",
+        );
+        let error = Err(IcsError::Parse {
+            line: 2,
+            message: "recurring event with more than 1000 occurrences".to_string(),
+        });
+        assert_eq!(parse(&text, None), error);
+        // only occurrences in the window count
+        assert_eq!(
+            parse(&text, Some((MAY + 1500. * DAY, MAY + 3000. * DAY)))
+                .unwrap()
+                .len(),
+            500
+        );
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART:20240501T100000Z\r\nRRULE:FREQ=DAILY\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(parse(&text, Some((MAY, MAY + 1500. * DAY))), error);
+    }
+
+    #[test]
+    fn test_time_zones() {
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;TZID=Europe/Paris:20240325T090000\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=2\r\nEND:VEVENT\r\n",
+        );
+        let events = parse(&text, None).unwrap();
+        let starts: Vec<f64> = events.iter().map(|e| e.bounds.0).collect();
+        // 08:00Z before daylight saving time, 07:00Z after
+        assert_eq!(starts, vec![1_711_353_600., 1_711_954_800.]);
+        let text =
+            calendar("BEGIN:VEVENT\r\nDTSTART;TZID=Mars/Olympus:20240325T090000\r\nEND:VEVENT\r\n");
+        assert_eq!(
+            parse(&text, None).unwrap_err().to_string(),
+            "line 3: unknown time zone `Mars/Olympus`"
+        );
+    }
+
+    #[test]
+    fn test_vtimezone() {
+        // Outlook time zone, defined after its events
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;TZID=W. Europe Standard Time:20240325T090000\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=2\r\nEND:VEVENT\r\n\
+             BEGIN:VTIMEZONE\r\nTZID:W. Europe Standard Time\r\n\
+             BEGIN:STANDARD\r\nDTSTART:16010101T030000\r\nTZOFFSETFROM:+0200\r\n\
+             TZOFFSETTO:+0100\r\nRRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10\r\nEND:STANDARD\r\n\
+             BEGIN:DAYLIGHT\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:+0100\r\n\
+             TZOFFSETTO:+0200\r\nRRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3\r\nEND:DAYLIGHT\r\n\
+             END:VTIMEZONE\r\n",
+        );
+        let events = parse(&text, None).unwrap();
+        let starts: Vec<f64> = events.iter().map(|e| e.bounds.0).collect();
+        // daylight saving time starts on March 31st 2024
+        assert_eq!(starts, vec![1_711_353_600., 1_711_954_800.]);
+    }
+
+    #[test]
+    fn test_out_of_range_recurrences() {
+        for rule in [
+            "FREQ=DAILY;INTERVAL=100000000;COUNT=5",
+            "FREQ=WEEKLY;INTERVAL=100000000;COUNT=5",
+            "FREQ=MONTHLY;INTERVAL=4000000000;COUNT=5",
+            "FREQ=YEARLY;INTERVAL=1000;COUNT=500",
+            // unbounded, until year 262142
+            "FREQ=YEARLY;INTERVAL=400",
+        ]
+        .iter()
+        {
+            let text = calendar(&format!(
+                "BEGIN:VEVENT\r\nDTSTART:20240229T100000Z\r\nRRULE:{}\r\nEND:VEVENT\r\n",
+                rule
+            ));
+            let events = parse(&text, None).unwrap();
+            assert!(!events.is_empty() && events.len() < 1000, "{}", rule);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        // folded properties are reported at their first line
+        let text = calendar("BEGIN:VEVENT\r\nDTSTART:2024\r\n 0501T1000\r\nEND:VEVENT\r\n");
+        assert_eq!(
+            parse(&text, None),
+            Err(IcsError::Parse {
+                line: 3,
+                message: "`20240501T1000` is not a date".to_string()
+            })
+        );
+        let text = calendar(
+            "BEGIN:VTIMEZONE\r\nTZID:Paris\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\n\
+             END:STANDARD\r\nEND:VTIMEZONE\r\n",
+        );
+        assert_eq!(
+            parse(&text, None).unwrap_err().to_string(),
+            "line 4: time zone observance without TZOFFSETTO"
+        );
+    }
+}
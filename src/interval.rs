@@ -1,5 +1,4 @@
 use crate::{Bound, EPSILON};
-use itertools::Itertools;
 use std::cmp::Ordering;
 use std::fmt::Debug;
 
@@ -31,7 +30,7 @@ where
 
 impl<L: Clone + Debug> PartialOrd for TBLInterval<L> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.bounds.0.partial_cmp(&other.bounds.0)
+        Some(self.cmp(other))
     }
 }
 
@@ -50,7 +49,7 @@ impl<L: Clone + Debug> Ord for TBLInterval<L> {
         if self.eq(other) {
             Ordering::Equal
         } else {
-            self.partial_cmp(other).unwrap()
+            self.bounds.0.total_cmp(&other.bounds.0)
         }
     }
 }
@@ -128,62 +127,87 @@ pub(crate) fn space_between<L: Clone + Debug>(
     TBLInterval::new((left_b, right_a), None)
 }
 
-fn overlaps_first<L: Clone + Debug>(
-    interval: &TBLInterval<L>,
-    sorted_non_overlapping_intervals: &[TBLInterval<L>],
-) -> bool {
-    match sorted_non_overlapping_intervals {
-        [] => false,
-        [first, _others @ ..] => intersect(interval, first),
+/// Split sorted intervals into non overlapping lanes: intervals are taken from last to first,
+/// each one by the first lane whose first interval it does not overlap.
+///
+/// The lanes are the ones obtained by selecting non overlapping intervals from last to first for
+/// the first lane, then for the next lane among the remaining intervals and so on, in
+/// `O(n log n)`.
+pub(crate) fn split_lanes<L: Clone + Debug>(
+    sorted_intervals: Vec<TBLInterval<L>>,
+) -> Vec<Vec<TBLInterval<L>>> {
+    let mut lanes: Vec<Vec<TBLInterval<L>>> = vec![];
+    let mut firsts = Starts::new(sorted_intervals.len());
+    for interval in sorted_intervals.into_iter().rev() {
+        let (a, b) = interval.bounds;
+        // not `intersect(interval, first)`
+        let lane = firsts
+            .first(|min, max| min < a || max >= b)
+            .unwrap_or(lanes.len());
+        if lane == lanes.len() {
+            lanes.push(vec![]);
+        }
+        firsts.set(lane, a);
+        lanes[lane].push(interval);
     }
+    for lane in lanes.iter_mut() {
+        lane.reverse();
+    }
+    lanes
 }
 
-pub(crate) fn split_overlapping<L: Clone + Debug>(
-    sorted_intervals: &[TBLInterval<L>],
-) -> Vec<Vec<TBLInterval<L>>> {
-    match sorted_intervals {
-        [] => vec![],
-        [first, others @ ..] => {
-            let non_overlapping_subsets = split_overlapping(others);
-            let (inserted, subsets): (bool, Vec<Vec<TBLInterval<L>>>) = non_overlapping_subsets
-                .iter()
-                .fold((false, vec![]), |(inserted, subsets), intervals| {
-                    if !inserted && !overlaps_first(first, intervals) {
-                        let prepended: Vec<TBLInterval<L>> = std::iter::once(first)
-                            .chain(intervals)
-                            .cloned()
-                            .collect_vec();
-                        let new_subsets: Vec<Vec<TBLInterval<L>>> = subsets
-                            .iter()
-                            .cloned()
-                            .chain(std::iter::once(prepended))
-                            .collect_vec();
-                        (true, new_subsets)
-                    } else {
-                        let new_subsets: Vec<Vec<TBLInterval<L>>> = subsets
-                            .iter()
-                            .cloned()
-                            .chain(std::iter::once(intervals.clone()))
-                            .collect_vec();
-                        (inserted, new_subsets)
-                    }
-                });
-            if inserted {
-                subsets
+/// Segment tree of the starts of the first interval of lanes: min and max of each subtree.
+struct Starts {
+    size: usize,
+    nodes: Vec<(f64, f64)>,
+}
+
+impl Starts {
+    fn new(lanes: usize) -> Self {
+        let size = lanes.next_power_of_two();
+        Starts {
+            size,
+            nodes: vec![(f64::INFINITY, f64::NEG_INFINITY); 2 * size],
+        }
+    }
+
+    fn set(&mut self, lane: usize, start: f64) {
+        let mut node = self.size + lane;
+        self.nodes[node] = (start, start);
+        while node > 1 {
+            node /= 2;
+            let (left, right) = (self.nodes[2 * node], self.nodes[2 * node + 1]);
+            self.nodes[node] = (left.0.min(right.0), left.1.max(right.1));
+        }
+    }
+
+    /// First lane with a start such that `fits(start, start)`, `fits(min, max)` must hold for a
+    /// subtree containing one.
+    fn first(&self, fits: impl Fn(f64, f64) -> bool) -> Option<usize> {
+        let (min, max) = self.nodes[1];
+        if !fits(min, max) {
+            return None;
+        }
+        let mut node = 1;
+        while node < self.size {
+            let (min, max) = self.nodes[2 * node];
+            node = if fits(min, max) {
+                2 * node
             } else {
-                subsets
-                    .iter()
-                    .cloned()
-                    .chain(std::iter::once(vec![first.clone()]))
-                    .collect_vec()
-            }
+                2 * node + 1
+            };
         }
+        Some(node - self.size)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::interval::{split_overlapping, TBLInterval};
+    use crate::interval::{split_lanes, TBLInterval};
+
+    fn split_overlapping(intervals: &[TBLInterval<String>]) -> Vec<Vec<TBLInterval<String>>> {
+        split_lanes(intervals.to_vec())
+    }
 
     #[test]
     fn test_split_overlapping() {
@@ -203,4 +227,69 @@ mod test {
         let non_overlapping_subsets = split_overlapping(intervals.as_slice());
         assert_eq!(non_overlapping_subsets.len(), 1);
     }
+
+    #[test]
+    fn test_split_lanes() {
+        let intervals: Vec<TBLInterval<String>> = vec![
+            TBLInterval::new((0.0, 2.0), None),
+            TBLInterval::new((1.0, 3.0), None),
+            TBLInterval::new((2.5, 4.0), None),
+            TBLInterval::new((3.5, 5.0), None),
+        ];
+        let bounds = |lanes: Vec<Vec<TBLInterval<String>>>| -> Vec<Vec<(f64, f64)>> {
+            lanes
+                .iter()
+                .map(|lane| lane.iter().map(|interval| interval.bounds).collect())
+                .collect()
+        };
+        // selected from the last interval
+        assert_eq!(
+            bounds(split_lanes(intervals)),
+            vec![vec![(1.0, 3.0), (3.5, 5.0)], vec![(0.0, 2.0), (2.5, 4.0)]]
+        );
+    }
+
+    #[test]
+    fn test_split_lanes_one_at_a_time() {
+        // lanes selected one at a time from last to first
+        fn take_lanes(mut intervals: Vec<TBLInterval<String>>) -> Vec<Vec<(f64, f64)>> {
+            let mut lanes = vec![];
+            while !intervals.is_empty() {
+                let mut first: Option<(f64, f64)> = None;
+                let mut lane = vec![];
+                let mut others = vec![];
+                for interval in intervals.into_iter().rev() {
+                    let (a, b) = interval.bounds;
+                    if first.is_some_and(|(start, _)| a <= start && start < b) {
+                        others.push(interval);
+                    } else {
+                        first = Some(interval.bounds);
+                        lane.push(interval.bounds);
+                    }
+                }
+                lane.reverse();
+                others.reverse();
+                lanes.push(lane);
+                intervals = others;
+            }
+            lanes
+        }
+        let mut seed: u64 = 42;
+        let mut random = |max: u64| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (seed >> 33) % max
+        };
+        let mut intervals: Vec<TBLInterval<String>> = (0..500)
+            .map(|_| {
+                let start = random(1000) as f64;
+                TBLInterval::new((start, start + 1. + random(50) as f64), None)
+            })
+            .collect();
+        intervals.sort();
+        let lanes: Vec<Vec<(f64, f64)>> = split_lanes(intervals.clone())
+            .iter()
+            .map(|lane| lane.iter().map(|interval| interval.bounds).collect())
+            .collect();
+        assert_eq!(lanes, take_lanes(intervals));
+    }
 }
@@ -0,0 +1,121 @@
+//! `tracing` layer recording entered spans, rendered as a timeline.
+
+use crate::recorder::thread_name;
+use crate::{Recorder, SpanRecord, TBLError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::Instant;
+use tracing_core::span::Id;
+use tracing_core::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Entered spans by thread.
+type Entered = HashMap<ThreadId, Vec<(Id, Instant)>>;
+
+/// Records the enter and exit times of spans per thread in memory, see `Recorder`.
+///
+/// Clones share the same buffer: keep a clone to render the spans recorded by an installed layer.
+///
+/// ```
+/// use tbl::TimelineLayer;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let layer = TimelineLayer::new();
+/// let subscriber = tracing_subscriber::registry().with(layer.clone());
+/// tracing::subscriber::with_default(subscriber, || {
+///     let _run = tracing::info_span!("run").entered();
+///     std::thread::sleep(std::time::Duration::from_millis(2));
+///     tracing::info_span!("step").in_scope(|| std::thread::sleep(std::time::Duration::from_millis(1)));
+/// });
+/// let spans = layer.spans();
+/// assert_eq!(spans.len(), 2);
+/// assert_eq!((spans[0].name.as_str(), spans[0].depth), ("run", 0));
+/// assert_eq!((spans[1].name.as_str(), spans[1].depth), ("step", 1));
+/// for line in layer.render(40).unwrap() {
+///     println!("{}", line);
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct TimelineLayer {
+    recorder: Recorder,
+    entered: Arc<Mutex<Entered>>,
+}
+
+impl TimelineLayer {
+    pub fn new() -> TimelineLayer {
+        TimelineLayer::default()
+    }
+
+    /// Recorded spans, sorted by enter time.
+    pub fn spans(&self) -> Vec<SpanRecord> {
+        self.recorder.spans()
+    }
+
+    /// Render recorded spans, see `Recorder::render`.
+    pub fn render(&self, length: usize) -> Result<Vec<String>, TBLError<String>> {
+        self.recorder.render(length)
+    }
+}
+
+impl<S> Layer<S> for TimelineLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &Id, _ctx: Context<'_, S>) {
+        let now = Instant::now();
+        self.entered
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(thread::current().id())
+            .or_default()
+            .push((id.clone(), now));
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let now = Instant::now();
+        let name = ctx.metadata(id).map_or("", |metadata| metadata.name());
+        let mut entered = self.entered.lock().unwrap_or_else(|e| e.into_inner());
+        let entered = entered.entry(thread::current().id()).or_default();
+        // spans are usually exited in reverse order
+        if let Some(position) = entered.iter().rposition(|(entered, _)| entered == id) {
+            let (_, enter) = entered.remove(position);
+            self.recorder
+                .record(name, &thread_name(), position, enter, now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::TimelineLayer;
+    use std::thread;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_threads() {
+        let layer = TimelineLayer::new();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let dispatch = tracing::dispatcher::get_default(Clone::clone);
+            let _main = tracing::info_span!("main").entered();
+            thread::Builder::new()
+                .name("worker".to_string())
+                .spawn(move || {
+                    tracing::dispatcher::with_default(&dispatch, || {
+                        let _work = tracing::info_span!("work").entered();
+                    })
+                })
+                .unwrap()
+                .join()
+                .unwrap();
+        });
+        let lanes: Vec<String> = layer.spans().iter().map(|span| span.lane()).collect();
+        assert_eq!(lanes.len(), 2);
+        assert!(lanes.contains(&"worker/0".to_string()));
+        let lines = layer.render(10).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().any(|line| line.starts_with("worker/0 ")));
+    }
+}
@@ -0,0 +1,164 @@
+//! Layout pass shared by all backends: intervals are grouped then split into non overlapping lanes.
+
+use crate::interval::{boundaries, is_empty, is_finite, split_lanes, union, TBLInterval};
+use crate::Bound;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Returns the group (swimlane) of a label.
+pub(crate) type GroupKey<'a, L> = &'a dyn Fn(&L) -> Option<String>;
+
+/// An interval placed in a lane.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment<L> {
+    pub bounds: Bound,
+    pub label: Option<L>,
+}
+
+/// Non overlapping segments sorted by start.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lane<L> {
+    pub segments: Vec<Segment<L>>,
+}
+
+/// Lanes of intervals sharing the same group (swimlane).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Group<L> {
+    /// `None` for intervals without group.
+    pub name: Option<String>,
+    pub lanes: Vec<Lane<L>>,
+}
+
+/// Groups of lanes, all sharing the same boundaries.
+///
+/// ```
+/// use tbl::{Bound, Renderer};
+/// let data: Vec<Bound> = vec![(0., 2.), (1., 3.), (4., 5.)];
+/// let layout = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>).layout();
+/// assert_eq!(layout.boundaries, Some((0., 5.)));
+/// assert_eq!(layout.groups.len(), 1);
+/// assert_eq!(layout.lanes().count(), 2);
+/// assert_eq!(layout.position(4., 10), 8.);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout<L> {
+    /// `None` if there are neither intervals nor configured boundaries.
+    pub boundaries: Option<Bound>,
+    pub groups: Vec<Group<L>>,
+}
+
+impl<L> Layout<L> {
+    /// All lanes, group by group.
+    pub fn lanes(&self) -> impl Iterator<Item = &Lane<L>> {
+        self.groups.iter().flat_map(|group| group.lanes.iter())
+    }
+
+    /// Position of `x` on a timeline `length` columns wide, not rounded.
+    pub fn position(&self, x: f64, length: usize) -> f64 {
+        match self.boundaries {
+            Some((min, max)) if max > min => (x - min) * (length as f64) / (max - min),
+            _ => 0.,
+        }
+    }
+}
+
+impl<L: Clone + Debug> Lane<L> {
+    pub(crate) fn intervals(&self) -> Vec<TBLInterval<L>> {
+        self.segments
+            .iter()
+            .map(|segment| TBLInterval::new(segment.bounds, segment.label.clone()))
+            .collect()
+    }
+}
+
+/// Intervals grouped but not yet split into lanes, lanes are taken one at a time.
+pub(crate) struct Grouped<L: Clone + Debug> {
+    pub(crate) boundaries: Option<Bound>,
+    /// Group names and their sorted intervals, in order of first appearance.
+    groups: Vec<(Option<String>, Vec<TBLInterval<L>>)>,
+}
+
+impl<L: Clone + Debug> Grouped<L> {
+    /// Lanes of each group, group by group.
+    pub(crate) fn into_lanes(self) -> impl Iterator<Item = Lane<L>> {
+        self.groups
+            .into_iter()
+            .flat_map(|(_, intervals)| group_lanes(intervals))
+    }
+}
+
+/// Lanes of sorted intervals.
+fn group_lanes<L: Clone + Debug>(intervals: Vec<TBLInterval<L>>) -> impl Iterator<Item = Lane<L>> {
+    split_lanes(intervals).into_iter().map(|lane| Lane {
+        segments: lane
+            .into_iter()
+            .map(|interval| Segment {
+                bounds: interval.bounds,
+                label: interval.label,
+            })
+            .collect(),
+    })
+}
+
+pub(crate) fn grouped<L: Clone + Debug>(
+    intervals: &[TBLInterval<L>],
+    configured_boundaries: Option<Bound>,
+    group: Option<GroupKey<L>>,
+) -> Grouped<L> {
+    let intervals: Vec<TBLInterval<L>> = intervals
+        .iter()
+        .filter(|interval| is_finite(interval))
+        .filter(|interval| !is_empty(interval))
+        .cloned()
+        .collect();
+    let layout_boundaries = match (boundaries(intervals.as_slice()), configured_boundaries) {
+        (None, _) => configured_boundaries,
+        (Some(b), None) => Some(b),
+        (Some(b), Some(other)) => Some(union(&b, &other)),
+    };
+    let group_name = |interval: &TBLInterval<L>| match (group, &interval.label) {
+        (Some(group), Some(label)) => group(label),
+        _ => None,
+    };
+    // groups in order of first appearance
+    let mut indices: HashMap<Option<String>, usize> = HashMap::new();
+    let mut groups: Vec<(Option<String>, Vec<TBLInterval<L>>)> = vec![];
+    for interval in intervals {
+        let name = group_name(&interval);
+        let index = *indices.entry(name.clone()).or_insert_with(|| {
+            groups.push((name, vec![]));
+            groups.len() - 1
+        });
+        groups[index].1.push(interval);
+    }
+    for (_, intervals) in groups.iter_mut() {
+        intervals.sort();
+    }
+    Grouped {
+        boundaries: layout_boundaries,
+        groups,
+    }
+}
+
+pub(crate) fn layout<L: Clone + Debug>(
+    intervals: &[TBLInterval<L>],
+    configured_boundaries: Option<Bound>,
+    group: Option<GroupKey<L>>,
+) -> Layout<L> {
+    let grouped = grouped(intervals, configured_boundaries, group);
+    Layout {
+        boundaries: grouped.boundaries,
+        groups: grouped
+            .groups
+            .into_iter()
+            .map(|(name, intervals)| Group {
+                name,
+                lanes: group_lanes(intervals).collect(),
+            })
+            .collect(),
+    }
+}
@@ -60,19 +60,61 @@
 //! }
 //! ```
 
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::io;
 use thiserror::Error;
 
+mod ansi;
 mod blocks;
 mod builder;
+mod calendar;
+#[cfg(feature = "gitlog")]
+pub mod gitlog;
+mod html;
+#[cfg(feature = "ics")]
+pub mod ics;
 mod interval;
+#[cfg(feature = "tracing")]
+mod layer;
+mod layout;
+#[cfg(feature = "logfile")]
+pub mod logfile;
+mod markdown;
+mod options;
+mod palette;
+mod pattern;
+mod recorder;
 mod rendering;
+mod segment;
+pub mod style;
+mod svg;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "timetracker")]
+pub mod timetracker;
+#[cfg(feature = "trace")]
+pub mod trace;
+mod vertical;
+mod wrap;
 
 pub(crate) const EPSILON: f64 = 0.1; // < 1/8
 pub type Bound = (f64, f64);
 
+pub use ansi::ColorLevel;
 pub use builder::Renderer;
+pub use calendar::{CalendarTime, Shading};
+#[cfg(feature = "tracing")]
+pub use layer::TimelineLayer;
+pub use layout::{Group, Lane, Layout, Segment};
+pub use options::Options;
+pub use palette::{Assignment, Palette};
+pub use pattern::Patterns;
+pub use recorder::{Recorder, SpanGuard, SpanRecord};
 pub use rendering::RenderBlock;
+pub use segment::SegmentStyle;
+pub use style::{Color, Line, Span, Style};
+pub use vertical::Orientation;
+pub use wrap::Wrap;
 
 /// Blocks are built, then rendered using a `BlockRenderer`.
 pub enum Block<L>
@@ -91,4 +133,47 @@ pub enum TBLError<L: Clone + Debug> {
     NoBoundaries,
     #[error("`{0:?}` intersects `{1:?}` ")]
     Intersection(Option<L>, Option<L>),
+    /// Writing the output failed, see `Renderer::render_to`.
+    #[error("io error ({0:?}): {1}")]
+    Io(io::ErrorKind, String),
+    /// Formatting the output failed, see `Renderer::render_to_fmt`.
+    #[error("formatting error")]
+    Fmt,
+    /// The output would have more rows (e.g. wrapped windows) than the limit.
+    #[error("{0} rows exceed the limit of {1} rows")]
+    TooManyRows(usize, usize),
+}
+
+impl<L: Clone + Debug> TBLError<L> {
+    /// Convert the labels of an intersection error using `f`, other errors are unchanged.
+    ///
+    /// ```
+    /// use tbl::TBLError;
+    /// let error: TBLError<(usize, &str)> = TBLError::Intersection(Some((0, "a")), None);
+    /// assert_eq!(
+    ///     error.map_label(|label| label.map(|(_, name)| name)),
+    ///     TBLError::Intersection(Some("a"), None)
+    /// );
+    /// ```
+    pub fn map_label<M: Clone + Debug>(self, f: impl Fn(Option<L>) -> Option<M>) -> TBLError<M> {
+        match self {
+            TBLError::NoBoundaries => TBLError::NoBoundaries,
+            TBLError::Intersection(left, right) => TBLError::Intersection(f(left), f(right)),
+            TBLError::Io(kind, message) => TBLError::Io(kind, message),
+            TBLError::Fmt => TBLError::Fmt,
+            TBLError::TooManyRows(count, max) => TBLError::TooManyRows(count, max),
+        }
+    }
+}
+
+impl<L: Clone + Debug> From<io::Error> for TBLError<L> {
+    fn from(e: io::Error) -> Self {
+        TBLError::Io(e.kind(), e.to_string())
+    }
+}
+
+impl<L: Clone + Debug> From<fmt::Error> for TBLError<L> {
+    fn from(_: fmt::Error) -> Self {
+        TBLError::Fmt
+    }
 }
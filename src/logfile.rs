@@ -0,0 +1,241 @@
+//! Plain-text log import: intervals are extracted from lines using regular expressions.
+
+use crate::Bound;
+use regex::{Captures, Regex};
+use std::collections::{HashMap, VecDeque};
+use thiserror::Error;
+
+/// An interval or a point event (start equals end) read from a log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Correlation key of intervals, captured text of events.
+    pub label: String,
+    pub start: f64,
+    /// `None` for started but not finished intervals.
+    pub end: Option<f64>,
+}
+
+impl LogEntry {
+    /// Start and end, open-ended intervals end at `last` (at their start if `last` is earlier).
+    pub fn bounds(&self, last: f64) -> Bound {
+        (self.start, self.end.unwrap_or_else(|| last.max(self.start)))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LogError {
+    #[error("{0}")]
+    Regex(#[from] regex::Error),
+    /// Lines are numbered from 1.
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+/// Patterns matched against each line of a log.
+///
+/// The timestamp of a line is the first capture group of the `timestamp` pattern, or its match.
+/// Intervals start on lines matching `start` and end on the next line matching `end` with the
+/// same key (first capture group, or match), events are lines matching `event`.
+///
+/// ```
+/// use tbl::logfile::LogPatterns;
+///
+/// let log = "\
+/// 10 INFO job build started
+/// 12 INFO job test started
+/// 13 WARN disk almost full
+/// 15 INFO job build finished
+/// 16 INFO job deploy started
+/// ";
+/// let patterns = LogPatterns::new(r"^(\d+) ")?
+///     .with_intervals(r"job (\w+) started", r"job (\w+) finished")?
+///     .with_events(r"WARN (.*)")?;
+/// let entries = patterns.parse(log, &mut |t| t.parse().map_err(|_| format!("`{}` is not a number", t)))?;
+/// let entries: Vec<(&str, f64, Option<f64>)> = entries
+///     .iter()
+///     .map(|e| (e.label.as_str(), e.start, e.end))
+///     .collect();
+/// assert_eq!(entries, vec![
+///     ("build", 10., Some(15.)),
+///     ("test", 12., None), // not finished
+///     ("disk almost full", 13., Some(13.)),
+///     ("deploy", 16., None),
+/// ]);
+/// # Ok::<(), tbl::logfile::LogError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogPatterns {
+    timestamp: Regex,
+    intervals: Option<(Regex, Regex)>,
+    events: Option<Regex>,
+}
+
+/// A line matching one of the patterns, starts and ends take precedence over events.
+enum Line<'t> {
+    Start(Captures<'t>),
+    End(Captures<'t>),
+    Event(Captures<'t>),
+}
+
+/// First capture group, or the whole match.
+fn capture(captures: &Captures) -> String {
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map_or_else(String::new, |m| m.as_str().to_string())
+}
+
+impl LogPatterns {
+    pub fn new(timestamp: &str) -> Result<LogPatterns, LogError> {
+        Ok(LogPatterns {
+            timestamp: Regex::new(timestamp)?,
+            intervals: None,
+            events: None,
+        })
+    }
+
+    /// Intervals from `start` lines to `end` lines.
+    pub fn with_intervals(self, start: &str, end: &str) -> Result<LogPatterns, LogError> {
+        Ok(LogPatterns {
+            intervals: Some((Regex::new(start)?, Regex::new(end)?)),
+            ..self
+        })
+    }
+
+    /// Point events from `event` lines.
+    pub fn with_events(self, event: &str) -> Result<LogPatterns, LogError> {
+        Ok(LogPatterns {
+            events: Some(Regex::new(event)?),
+            ..self
+        })
+    }
+
+    /// Entries of `log` sorted by start, `time` converts timestamps to bounds.
+    ///
+    /// Lines without timestamp are ignored, as are ends without start.
+    pub fn parse(
+        &self,
+        log: &str,
+        time: &mut dyn FnMut(&str) -> Result<f64, String>,
+    ) -> Result<Vec<LogEntry>, LogError> {
+        let mut entries = vec![];
+        // indices of open intervals by key, in start order
+        let mut open: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (i, line) in log.lines().enumerate() {
+            let timestamp = match self.timestamp.captures(line) {
+                Some(captures) => capture(&captures),
+                None => continue,
+            };
+            let line_kind = self
+                .intervals
+                .as_ref()
+                .and_then(|(start, end)| {
+                    start
+                        .captures(line)
+                        .map(Line::Start)
+                        .or_else(|| end.captures(line).map(Line::End))
+                })
+                .or_else(|| {
+                    self.events
+                        .as_ref()
+                        .and_then(|event| event.captures(line))
+                        .map(Line::Event)
+                });
+            let line_kind = match line_kind {
+                Some(line_kind) => line_kind,
+                None => continue,
+            };
+            let at = time(&timestamp).map_err(|message| LogError::Parse {
+                line: i + 1,
+                message,
+            })?;
+            match line_kind {
+                Line::Start(captures) => {
+                    let key = capture(&captures);
+                    open.entry(key.clone())
+                        .or_default()
+                        .push_back(entries.len());
+                    entries.push(LogEntry {
+                        label: key,
+                        start: at,
+                        end: None,
+                    });
+                }
+                Line::End(captures) => {
+                    let started = open
+                        .get_mut(&capture(&captures))
+                        .and_then(VecDeque::pop_front);
+                    if let Some(index) = started {
+                        entries[index].end = Some(at);
+                    }
+                }
+                Line::Event(captures) => entries.push(LogEntry {
+                    label: capture(&captures),
+                    start: at,
+                    end: Some(at),
+                }),
+            }
+        }
+        entries.sort_by(|a, b| a.start.total_cmp(&b.start));
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::logfile::{LogEntry, LogError, LogPatterns};
+
+    fn seconds(time: &str) -> Result<f64, String> {
+        let parts: Vec<f64> = time.split(':').filter_map(|p| p.parse().ok()).collect();
+        match parts.as_slice() {
+            [h, m, s] => Ok(h * 3600. + m * 60. + s),
+            _ => Err(format!("`{}` is not a time", time)),
+        }
+    }
+
+    #[test]
+    fn test_pairs() {
+        let log = "[10:00:00] worker 1: start upload\n\
+                   continuation line without timestamp: start upload\n\
+                   [10:00:05] worker 2: start upload\n\
+                   [10:00:07] worker 1: done upload\n\
+                   [10:00:08] worker 1: done upload\n\
+                   [10:00:09] worker 2: done upload\n";
+        let patterns = LogPatterns::new(r"^\[([\d:]+)\]")
+            .unwrap()
+            .with_intervals(r"start (\w+)", r"done (\w+)")
+            .unwrap();
+        let entries = patterns.parse(log, &mut seconds).unwrap();
+        // ends are paired with the first open start of the same key
+        assert_eq!(
+            entries,
+            vec![
+                LogEntry {
+                    label: "upload".to_string(),
+                    start: 36_000.,
+                    end: Some(36_007.)
+                },
+                LogEntry {
+                    label: "upload".to_string(),
+                    start: 36_005.,
+                    end: Some(36_008.)
+                },
+            ]
+        );
+        assert_eq!(entries[0].bounds(0.), (36_000., 36_007.));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(LogPatterns::new("("), Err(LogError::Regex(_))));
+        let patterns = LogPatterns::new(r"^(\S+)")
+            .unwrap()
+            .with_events("ERROR")
+            .unwrap();
+        // timestamps are only parsed on lines matching a pattern
+        let log = "soon ok\n\n10:00:00 ERROR\n";
+        assert_eq!(patterns.parse(log, &mut seconds).unwrap().len(), 1);
+        let error = patterns.parse("soon ERROR\n", &mut seconds).unwrap_err();
+        assert_eq!(error.to_string(), "line 1: `soon` is not a time");
+    }
+}
@@ -0,0 +1,77 @@
+//! Markdown backend: the timeline in a fenced code block followed by a table of intervals.
+
+use crate::layout::Layout;
+use itertools::Itertools;
+use std::fmt::Write;
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// `lines` are the rendered timeline lines, without escape codes.
+pub(crate) fn render_markdown<L>(
+    layout: &Layout<L>,
+    lines: &[String],
+    label: &dyn Fn(&L) -> String,
+    bound: &dyn Fn(f64) -> String,
+    duration: &dyn Fn(f64) -> String,
+) -> String {
+    let grouped = layout.groups.iter().any(|group| group.name.is_some());
+    let mut report = String::new();
+    let fence = if lines.iter().any(|line| line.contains("```")) {
+        "~~~"
+    } else {
+        "```"
+    };
+    let _ = writeln!(report, "{}text", fence);
+    for line in lines {
+        let _ = writeln!(report, "{}", line);
+    }
+    let _ = writeln!(report, "{}", fence);
+    let _ = writeln!(report);
+    if grouped {
+        let _ = writeln!(report, "| label | start | end | duration | group | lane |");
+        let _ = writeln!(report, "|---|---|---|---|---|---|");
+    } else {
+        let _ = writeln!(report, "| label | start | end | duration | lane |");
+        let _ = writeln!(report, "|---|---|---|---|---|");
+    }
+    let rows = layout
+        .groups
+        .iter()
+        .flat_map(|group| group.lanes.iter().map(move |lane| (group, lane)))
+        .enumerate()
+        .flat_map(|(index, (group, lane))| {
+            lane.segments
+                .iter()
+                .map(move |segment| (index, group, segment))
+        })
+        .sorted_by(|(li, _, left), (ri, _, right)| {
+            left.bounds
+                .0
+                .partial_cmp(&right.bounds.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(li.cmp(ri))
+        });
+    for (index, group, segment) in rows {
+        let (a, b) = segment.bounds;
+        let text = segment.label.as_ref().map(label).unwrap_or_default();
+        let _ = write!(
+            report,
+            "| {} | {} | {} | {} |",
+            cell(&text),
+            cell(&bound(a)),
+            cell(&bound(b)),
+            cell(&duration(b - a))
+        );
+        if grouped {
+            let _ = write!(
+                report,
+                " {} |",
+                cell(group.name.as_deref().unwrap_or_default())
+            );
+        }
+        let _ = writeln!(report, " {} |", index + 1);
+    }
+    report
+}
@@ -0,0 +1,30 @@
+//! Rendering options independent of the label type, e.g. read from a configuration file.
+
+use crate::ansi::ColorLevel;
+use crate::segment::SegmentStyle;
+use crate::vertical::Orientation;
+use crate::Bound;
+
+/// Options applied by `Renderer::with_options`, unset options are left unchanged.
+///
+/// With the `serde` feature, options can be deserialized (missing fields are unset).
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Options {
+    /// see `Renderer::with_length`
+    pub length: Option<usize>,
+    /// see `Renderer::with_boundaries`
+    pub boundaries: Option<Bound>,
+    /// see `Renderer::with_color_level`
+    pub color_level: Option<ColorLevel>,
+    /// see `Renderer::with_segment_style`
+    pub segment_style: Option<SegmentStyle>,
+    /// see `Renderer::with_orientation`
+    pub orientation: Option<Orientation>,
+    /// see `Renderer::with_lane_width`
+    pub lane_width: Option<usize>,
+}
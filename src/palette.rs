@@ -0,0 +1,148 @@
+//! Automatic per-label colors.
+
+use crate::style::{Color, Line, Span, Style};
+
+/// Okabe-Ito colorblind-safe palette (black replaced by grey).
+const OKABE_ITO: [Color; 8] = [
+    Color::Rgb(230, 159, 0),
+    Color::Rgb(86, 180, 233),
+    Color::Rgb(0, 158, 115),
+    Color::Rgb(240, 228, 66),
+    Color::Rgb(0, 114, 178),
+    Color::Rgb(213, 94, 0),
+    Color::Rgb(204, 121, 167),
+    Color::Rgb(153, 153, 153),
+];
+
+/// How a palette entry is chosen for a given label key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Assignment {
+    /// The key is hashed, a key always gets the same color whatever the data.
+    Hash,
+    /// Keys get palette entries in order of first appearance.
+    FirstAppearance,
+}
+
+/// A categorical palette.
+///
+/// ```
+/// use tbl::{Assignment, Color, Palette};
+/// let palette = Palette::new(vec![Color::Red, Color::Blue], Assignment::FirstAppearance);
+/// assert_eq!(palette.color("launch", 0), Some(Color::Red));
+/// assert_eq!(palette.color("moon walk", 1), Some(Color::Blue));
+/// assert_eq!(palette.color("breakfast", 2), Some(Color::Red));
+/// let palette = Palette::colorblind_safe(Assignment::Hash);
+/// assert_eq!(palette.color("launch", 0), palette.color("launch", 42));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    colors: Vec<Color>,
+    assignment: Assignment,
+}
+
+impl Palette {
+    pub fn new(colors: Vec<Color>, assignment: Assignment) -> Self {
+        Palette { colors, assignment }
+    }
+
+    /// Okabe-Ito colorblind-safe palette.
+    pub fn colorblind_safe(assignment: Assignment) -> Self {
+        Palette::new(OKABE_ITO.to_vec(), assignment)
+    }
+
+    /// Color for a label `key`, `index` is the rank of first appearance of `key`.
+    ///
+    /// Returns `None` if the palette is empty.
+    pub fn color(&self, key: &str, index: usize) -> Option<Color> {
+        if self.colors.is_empty() {
+            return None;
+        }
+        let i = match self.assignment {
+            Assignment::Hash => (fnv1a(key) % self.colors.len() as u64) as usize,
+            Assignment::FirstAppearance => index % self.colors.len(),
+        };
+        Some(self.colors[i])
+    }
+}
+
+/// FNV-1a, stable across runs and platforms unlike `DefaultHasher`.
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Distinct label keys in order of first appearance.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Categories {
+    keys: Vec<String>,
+}
+
+impl Categories {
+    pub(crate) fn new<'k>(keys: impl Iterator<Item = &'k str>) -> Self {
+        let mut distinct: Vec<String> = vec![];
+        for key in keys {
+            if !distinct.iter().any(|k| k == key) {
+                distinct.push(key.to_string());
+            }
+        }
+        Categories { keys: distinct }
+    }
+
+    pub(crate) fn index(&self, key: &str) -> Option<usize> {
+        self.keys.iter().position(|k| k == key)
+    }
+
+    pub(crate) fn keys(&self) -> &[String] {
+        self.keys.as_slice()
+    }
+}
+
+/// Categories of the palette and of the patterns, each computed using its own key.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CategorySets {
+    pub(crate) colors: Categories,
+    pub(crate) patterns: Categories,
+}
+
+/// A legend line: category key, color and pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LegendEntry {
+    pub(crate) key: String,
+    pub(crate) color: Option<Color>,
+    pub(crate) pattern: Option<char>,
+}
+
+/// Apply `color` as background to spans without background.
+pub(crate) fn colorize(line: &[Span], color: Color) -> Line {
+    line.iter()
+        .map(|span| {
+            let style = Style {
+                bg: span.style.bg.or(Some(color)),
+                ..span.style
+            };
+            Span::styled(span.text.clone(), style)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::palette::{fnv1a, Categories};
+
+    #[test]
+    fn test_categories() {
+        let categories = Categories::new(vec!["b", "a", "b", "c"].into_iter());
+        assert_eq!(categories.keys(), &["b", "a", "c"]);
+        assert_eq!(categories.index("c"), Some(2));
+        assert_eq!(categories.index("d"), None);
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
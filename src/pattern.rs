@@ -0,0 +1,143 @@
+//! Monochrome pattern fills.
+
+use crate::Block;
+use itertools::repeat_n;
+use std::fmt::Debug;
+use std::iter;
+
+const DEFAULT_PATTERNS: [char; 7] = ['█', '▓', '▒', '░', '▞', '▚', '#'];
+/// Fill of segments without category.
+const UNCATEGORIZED: char = '=';
+/// Marks the start of a segment touching a segment with the same fill.
+const SEPARATOR: char = '│';
+
+/// Fill characters assigned to categories in order of first appearance.
+///
+/// ```
+/// use tbl::Patterns;
+/// let patterns = Patterns::default();
+/// assert_eq!(patterns.pattern(0), Some('█'));
+/// assert_eq!(patterns.pattern(7), Some('█'));
+/// assert_eq!(Patterns::new(vec![]).pattern(0), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Patterns {
+    patterns: Vec<char>,
+}
+
+impl Patterns {
+    pub fn new(patterns: Vec<char>) -> Self {
+        Patterns { patterns }
+    }
+
+    /// Fill for the category with rank of first appearance `index`.
+    ///
+    /// Returns `None` if there are no patterns.
+    pub fn pattern(&self, index: usize) -> Option<char> {
+        if self.patterns.is_empty() {
+            None
+        } else {
+            Some(self.patterns[index % self.patterns.len()])
+        }
+    }
+}
+
+impl Default for Patterns {
+    /// `█ ▓ ▒ ░ ▞ ▚ #`
+    fn default() -> Self {
+        Patterns::new(DEFAULT_PATTERNS.to_vec())
+    }
+}
+
+/// How a segment is filled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Fill {
+    pattern: char,
+    /// the segment touches a previous segment with the same pattern
+    separated: bool,
+}
+
+impl Fill {
+    pub(crate) fn pattern(&self) -> char {
+        self.pattern
+    }
+
+    pub(crate) fn separated(&self) -> bool {
+        self.separated
+    }
+
+    /// Separated one column segments are only the separator, so that they never merge with the
+    /// previous segment.
+    pub(crate) fn render(&self, length: usize) -> String {
+        match length {
+            0 => String::new(),
+            _ if self.separated => iter::once(SEPARATOR)
+                .chain(repeat_n(self.pattern, length - 1))
+                .collect(),
+            _ => repeat_n(self.pattern, length).collect(),
+        }
+    }
+}
+
+/// Fills of `blocks` (`None` for spaces).
+///
+/// `pattern` returns the pattern of a label, adjacent segments with the same pattern are separated.
+pub(crate) fn fills<L: Clone + Debug>(
+    blocks: &[Block<L>],
+    pattern: &dyn Fn(&L) -> Option<char>,
+) -> Vec<Option<Fill>> {
+    let mut previous: Option<char> = None;
+    blocks
+        .iter()
+        .map(|block| match block {
+            Block::Space(0) => None,
+            Block::Space(_) => {
+                previous = None;
+                None
+            }
+            Block::Segment(length, label) => {
+                let fill = label.as_ref().and_then(pattern).unwrap_or(UNCATEGORIZED);
+                let separated = previous == Some(fill);
+                if *length > 0 {
+                    previous = Some(fill);
+                }
+                Some(Fill {
+                    pattern: fill,
+                    separated,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pattern::fills;
+    use crate::Block;
+
+    #[test]
+    fn test_fills() {
+        let blocks: Vec<Block<char>> = vec![
+            Block::Segment(3, Some('a')),
+            Block::Space(0),
+            Block::Segment(3, Some('a')),
+            Block::Space(0),
+            Block::Segment(3, Some('b')),
+            Block::Space(1),
+            Block::Segment(3, Some('b')),
+            Block::Space(0),
+            Block::Segment(1, Some('b')),
+        ];
+        let rendered: Vec<String> = fills(blocks.as_slice(), &|&l| Some(l))
+            .iter()
+            .zip(blocks.iter())
+            .map(|(fill, block)| match (fill, block) {
+                (Some(fill), Block::Segment(length, _)) => fill.render(*length),
+                (_, Block::Space(length)) => " ".repeat(*length),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(rendered.concat(), "aaa│aabbb bbb│");
+    }
+}
@@ -0,0 +1,274 @@
+//! Std-only recording of timed code sections, rendered as a timeline.
+
+use crate::{Block, Bound, RenderBlock, Renderer, TBLError};
+use itertools::repeat_n;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, ThreadId};
+use std::time::Instant;
+
+/// A span entered then exited by a thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRecord {
+    /// Span name.
+    pub name: String,
+    /// Thread name, or id for unnamed threads.
+    pub thread: String,
+    /// Number of spans entered by the thread when this span was entered.
+    pub depth: usize,
+    /// Enter and exit times, in microseconds since the recorder was created.
+    pub bounds: Bound,
+}
+
+impl SpanRecord {
+    /// Swimlane of the span: `thread/depth`.
+    pub fn lane(&self) -> String {
+        format!("{}/{}", self.thread, self.depth)
+    }
+}
+
+#[derive(Default)]
+struct Buffer {
+    /// Number of entered spans by thread.
+    depths: HashMap<ThreadId, usize>,
+    spans: Vec<SpanRecord>,
+}
+
+/// Records timed code sections (spans) per thread in memory.
+///
+/// Clones share the same buffer, e.g. to record spans from other threads.
+///
+/// ```
+/// use tbl::Recorder;
+///
+/// let recorder = Recorder::new();
+/// {
+///     let _run = recorder.span("run");
+///     std::thread::sleep(std::time::Duration::from_millis(2));
+///     let _step = recorder.span("step");
+///     std::thread::sleep(std::time::Duration::from_millis(1));
+/// }
+/// let spans = recorder.spans();
+/// assert_eq!(spans.len(), 2);
+/// assert_eq!((spans[0].name.as_str(), spans[0].depth), ("run", 0));
+/// assert_eq!((spans[1].name.as_str(), spans[1].depth), ("step", 1));
+/// for line in recorder.render(40).unwrap() {
+///     println!("{}", line);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Recorder {
+    origin: Instant,
+    buffer: Arc<Mutex<Buffer>>,
+}
+
+/// Records a span when dropped, see `Recorder::span`.
+///
+/// The span belongs to the thread which entered it, even if the guard is dropped by another one.
+#[must_use = "the span is recorded when the guard is dropped"]
+pub struct SpanGuard<'a> {
+    recorder: &'a Recorder,
+    name: String,
+    thread: ThreadId,
+    thread_name: String,
+    depth: usize,
+    enter: Instant,
+}
+
+impl Drop for SpanGuard<'_> {
+    fn drop(&mut self) {
+        let exit = Instant::now();
+        let mut buffer = self.recorder.buffer();
+        if let Some(depth) = buffer.depths.get_mut(&self.thread) {
+            *depth = depth.saturating_sub(1);
+        }
+        drop(buffer);
+        self.recorder
+            .record(&self.name, &self.thread_name, self.depth, self.enter, exit);
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Recorder::new()
+    }
+}
+
+/// Name of the current thread, or its id if unnamed.
+pub(crate) fn thread_name() -> String {
+    let thread = thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+fn render_name(b: &Block<SpanRecord>) -> RenderBlock {
+    match b {
+        Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+        Block::Segment(length, span) => {
+            let name: String = span
+                .as_ref()
+                .map(|span| span.name.chars().take(*length).collect())
+                .unwrap_or_default();
+            let padding = "=".repeat(*length - name.chars().count());
+            RenderBlock::Block(name + &padding)
+        }
+    }
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder {
+            origin: Instant::now(),
+            buffer: Arc::new(Mutex::new(Buffer::default())),
+        }
+    }
+
+    fn buffer(&self) -> MutexGuard<'_, Buffer> {
+        // a panic while recording leaves the buffer consistent
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start a span on the current thread, it ends when the returned guard is dropped.
+    pub fn span(&self, name: impl Into<String>) -> SpanGuard<'_> {
+        let thread = thread::current().id();
+        let mut buffer = self.buffer();
+        let depth = buffer.depths.entry(thread).or_default();
+        let guard = SpanGuard {
+            recorder: self,
+            name: name.into(),
+            thread,
+            thread_name: thread_name(),
+            depth: *depth,
+            enter: Instant::now(),
+        };
+        *depth += 1;
+        guard
+    }
+
+    /// Record a span of `thread`.
+    pub(crate) fn record(
+        &self,
+        name: &str,
+        thread: &str,
+        depth: usize,
+        enter: Instant,
+        exit: Instant,
+    ) {
+        let micros =
+            |instant: Instant| instant.saturating_duration_since(self.origin).as_secs_f64() * 1e6;
+        let span = SpanRecord {
+            name: name.to_string(),
+            thread: thread.to_string(),
+            depth,
+            bounds: (micros(enter), micros(exit)),
+        };
+        self.buffer().spans.push(span);
+    }
+
+    /// Recorded spans, sorted by enter time.
+    pub fn spans(&self) -> Vec<SpanRecord> {
+        let mut spans = self.buffer().spans.clone();
+        spans.sort_by(|a, b| a.bounds.0.total_cmp(&b.bounds.0));
+        spans
+    }
+
+    /// Render recorded spans, one swimlane per thread and depth prefixed by its name
+    /// (see `SpanRecord::lane`), the timeline is `length` characters wide.
+    ///
+    /// Threads are rendered in order of their first span.
+    pub fn render(&self, length: usize) -> Result<Vec<String>, TBLError<String>> {
+        let mut spans = self.spans();
+        // threads in order of first span, then depths
+        let mut order: HashMap<String, usize> = HashMap::new();
+        for span in spans.iter() {
+            let next = order.len();
+            order.entry(span.thread.clone()).or_insert(next);
+        }
+        spans.sort_by_key(|span| (order[&span.thread], span.depth));
+        let lane = |span: &SpanRecord| Some(span.lane());
+        let mut renderer = Renderer::new(spans.as_slice(), &|span| span.bounds, &|span| {
+            Some(span.clone())
+        });
+        let renderer = renderer
+            .with_length(length)
+            .with_groups(&lane)
+            .with_renderer(&render_name);
+        let names: Vec<String> = renderer
+            .layout()
+            .groups
+            .iter()
+            .flat_map(|group| repeat_n(group.name.clone().unwrap_or_default(), group.lanes.len()))
+            .collect();
+        let width = names.iter().map(|name| name.chars().count()).max();
+        Ok(names
+            .iter()
+            .zip(
+                renderer
+                    .render()
+                    .map_err(|e| e.map_label(|span| span.map(|span| span.name)))?,
+            )
+            .flat_map(|(name, lines)| {
+                lines
+                    .into_iter()
+                    .map(move |line| format!("{:w$} {}", name, line, w = width.unwrap_or(0)))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Recorder;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_threads() {
+        let recorder = Recorder::new();
+        let _main = recorder.span("main");
+        let worker = recorder.clone();
+        thread::Builder::new()
+            .name("worker".to_string())
+            .spawn(move || {
+                let _work = worker.span("work");
+                let _nested = worker.span("nested");
+                thread::sleep(Duration::from_millis(1));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        let lanes: Vec<String> = recorder.spans().iter().map(|span| span.lane()).collect();
+        assert_eq!(lanes, vec!["worker/0", "worker/1"]);
+        let lines = recorder.render(10).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("worker/0 "));
+    }
+
+    #[test]
+    fn test_guard_dropped_by_another_thread() {
+        let recorder = Recorder::new();
+        thread::Builder::new()
+            .name("enter".to_string())
+            .spawn({
+                let recorder = recorder.clone();
+                move || {
+                    let guard = recorder.span("moved");
+                    thread::scope(|scope| {
+                        thread::Builder::new()
+                            .name("drop".to_string())
+                            .spawn_scoped(scope, move || drop(guard))
+                            .unwrap();
+                    });
+                    // the depth of the entering thread is restored
+                    let _next = recorder.span("next");
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        let spans: Vec<String> = recorder.spans().iter().map(|span| span.lane()).collect();
+        assert_eq!(spans, vec!["enter/0", "enter/0"]);
+    }
+}
@@ -1,7 +1,6 @@
+use crate::style::{fit, Line, Span};
 use crate::Block;
-use itertools::{repeat_n, Itertools};
 use std::fmt::Debug;
-use std::iter::once;
 
 const TEXT_FULL: &str = "=";
 const TEXT_EMPTY: &str = " ";
@@ -19,52 +18,48 @@ pub enum RenderBlock {
     MultiLineSpace(Vec<String>),
     /// A multi line block
     MultiLineBlock(Vec<String>),
+    /// A (multi line) block made of styled spans, one `Line` per output line.
+    ///
+    /// Each line is truncated or padded to the block width.
+    StyledBlock(Vec<Line>),
+    /// A (multi line) space made of styled spans, one `Line` per output line.
+    ///
+    /// Each line is truncated or padded to the space width.
+    StyledSpace(Vec<Line>),
 }
 
-#[derive(Debug, Clone)]
-pub(crate) enum SingleLineRenderBlock {
-    Block(String),
-    Space(String),
-}
-
-impl From<&SingleLineRenderBlock> for String {
-    fn from(rb: &SingleLineRenderBlock) -> Self {
-        match rb {
-            SingleLineRenderBlock::Block(s) => s.clone(),
-            SingleLineRenderBlock::Space(s) => s.clone(),
-        }
-    }
-}
-
-fn single_line(render_block: &RenderBlock) -> Option<SingleLineRenderBlock> {
+/// The line of a block rendered for vertical padding, `None` if multiline.
+fn single_line(render_block: RenderBlock, width: usize) -> Option<Line> {
     match render_block {
-        RenderBlock::Block(s) => Some(SingleLineRenderBlock::Block(s.clone())),
-        RenderBlock::Space(s) => Some(SingleLineRenderBlock::Space(s.clone())),
+        RenderBlock::Block(s) | RenderBlock::Space(s) => Some(vec![Span::raw(s)]),
+        RenderBlock::StyledBlock(mut v) | RenderBlock::StyledSpace(mut v) if v.len() == 1 => {
+            v.pop().map(|l| fit(&l, width))
+        }
         _ => None,
     }
 }
 
-fn break_down(render_block: &RenderBlock) -> Vec<SingleLineRenderBlock> {
+/// Lines of a block, styled lines are fitted to `width`.
+fn break_down(render_block: RenderBlock, width: usize) -> Vec<Line> {
     match render_block {
-        RenderBlock::Block(s) => vec![SingleLineRenderBlock::Block(s.clone())],
-        RenderBlock::Space(s) => vec![SingleLineRenderBlock::Space(s.clone())],
-        RenderBlock::MultiLineSpace(v) => v
-            .iter()
-            .map(|s| SingleLineRenderBlock::Space(s.clone()))
-            .collect(),
-        RenderBlock::MultiLineBlock(v) => v
-            .iter()
-            .map(|s| SingleLineRenderBlock::Block(s.clone()))
-            .collect(),
+        RenderBlock::Block(s) | RenderBlock::Space(s) => vec![vec![Span::raw(s)]],
+        RenderBlock::MultiLineBlock(v) | RenderBlock::MultiLineSpace(v) => {
+            v.into_iter().map(|s| vec![Span::raw(s)]).collect()
+        }
+        RenderBlock::StyledBlock(v) | RenderBlock::StyledSpace(v) => {
+            v.iter().map(|l| fit(l, width)).collect()
+        }
     }
 }
 
-fn lines(render_block: &RenderBlock) -> usize {
+/// Styled lines of a render block, plain strings are converted to unstyled spans.
+pub(crate) fn styled_lines(render_block: &RenderBlock) -> Vec<Line> {
     match render_block {
-        RenderBlock::Block(_) => 1,
-        RenderBlock::Space(_) => 1,
-        RenderBlock::MultiLineSpace(v) => v.len(),
-        RenderBlock::MultiLineBlock(v) => v.len(),
+        RenderBlock::Block(s) | RenderBlock::Space(s) => vec![vec![Span::raw(s.clone())]],
+        RenderBlock::MultiLineBlock(v) | RenderBlock::MultiLineSpace(v) => {
+            v.iter().map(|s| vec![Span::raw(s.clone())]).collect()
+        }
+        RenderBlock::StyledBlock(v) | RenderBlock::StyledSpace(v) => v.clone(),
     }
 }
 
@@ -78,69 +73,84 @@ where
     }
 }
 
-fn uncons<T: Clone>(v: Vec<T>) -> Option<(T, Vec<T>)> {
-    match v.as_slice() {
-        [] => None,
-        [a, ..] => Some((a.clone(), v[1..].to_vec())),
-    }
-}
-
-fn traverse<A: Clone, B>(f: &dyn Fn(A) -> Option<B>, a: Vec<A>) -> Option<Vec<B>> {
-    match uncons(a) {
-        None => Some(vec![]),
-        Some((h, t)) => match (f(h), traverse(f, t)) {
-            (Some(hh), Some(tt)) => Some(once(hh).chain(tt).collect()),
-            _ => None,
-        },
-    }
-}
-
-fn transpose<T: Clone>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    match traverse(&uncons, v) {
-        None => vec![],
-        Some(couples) => {
-            let head = couples.iter().map(|e| e.0.clone()).collect();
-            let tail = transpose(couples.iter().map(|e| e.1.clone()).collect());
-            once(head).chain(tail).collect()
-        }
-    }
-}
-
+/// Render `blocks` as lines, blocks with fewer lines are padded using the rendering of a space.
+///
+/// `renderer` is given the index of the block in `blocks`.
 pub(crate) fn render_blocks<L: Clone + Debug>(
     blocks: &[Block<L>],
-    renderer: &dyn Fn(&Block<L>) -> RenderBlock,
-) -> Vec<Vec<SingleLineRenderBlock>> {
-    let rendered: Vec<(usize, RenderBlock)> = blocks
+    renderer: &dyn Fn(usize, &Block<L>) -> RenderBlock,
+) -> Vec<Line> {
+    let columns: Vec<(usize, Vec<Line>)> = blocks
         .iter()
-        .map(|b| match b {
-            Block::Space(w) => (*w, renderer(b)),
-            Block::Segment(w, _) => (*w, renderer(b)),
+        .enumerate()
+        .map(|(i, b)| match b {
+            Block::Space(w) | Block::Segment(w, _) => (*w, break_down(renderer(i, b), *w)),
         })
         .collect();
-
-    let nb_lines = rendered
+    let nb_lines = columns
         .iter()
-        .map(|(_w, rb)| lines(rb))
+        .map(|(_w, lines)| lines.len())
         .max()
         .unwrap_or_default();
+    let mut rows: Vec<Line> = (0..nb_lines)
+        .map(|_| Line::with_capacity(columns.len()))
+        .collect();
+    for (i, (width, lines)) in columns.into_iter().enumerate() {
+        let height = lines.len();
+        for (row, line) in rows.iter_mut().zip(lines) {
+            row.extend(line);
+        }
+        if height < nb_lines {
+            let space = single_line(renderer(i, &Block::Space(width)), width)
+                .unwrap_or_else(|| vec![Span::raw(" ".repeat(width))]);
+            for row in &mut rows[height..] {
+                row.extend(space.iter().cloned());
+            }
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rendering::{render_blocks, RenderBlock};
+    use crate::style::plain;
+    use crate::Block;
 
-    fn pad_vertically<L: Clone + Debug>(
-        render_block: &RenderBlock,
-        width: usize,
-        lines: usize,
-        renderer: &dyn Fn(&Block<L>) -> RenderBlock,
-    ) -> Vec<SingleLineRenderBlock> {
-        let top = break_down(render_block);
-        let space = single_line(&renderer(&Block::Space(width)))
-            .unwrap_or_else(|| SingleLineRenderBlock::Space("".repeat(width)));
-        let vpad = repeat_n(space, lines - top.len()).collect_vec();
-        top.into_iter().chain(vpad.into_iter()).collect()
+    fn render(_: usize, b: &Block<()>) -> RenderBlock {
+        match b {
+            Block::Space(w) => RenderBlock::Space(".".repeat(*w)),
+            Block::Segment(w, _) => RenderBlock::MultiLineBlock(vec!["=".repeat(*w); 2]),
+        }
     }
 
-    let columns: Vec<Vec<SingleLineRenderBlock>> = rendered
-        .iter()
-        .map(|(w, rb)| pad_vertically(rb, *w, nb_lines, &renderer))
-        .collect();
+    #[test]
+    fn test_render_blocks() {
+        let blocks = vec![
+            Block::Segment(2, None),
+            Block::Space(1),
+            Block::Segment(1, None),
+        ];
+        let rows: Vec<String> = render_blocks(blocks.as_slice(), &render)
+            .iter()
+            .map(|line| plain(line))
+            .collect();
+        assert_eq!(rows, vec!["==.=", "==.="]);
+    }
 
-    transpose(columns)
+    #[test]
+    fn test_render_many_blocks() {
+        let blocks: Vec<Block<()>> = (0..100_000)
+            .map(|i| {
+                if i % 2 == 0 {
+                    Block::Segment(1, None)
+                } else {
+                    Block::Space(1)
+                }
+            })
+            .collect();
+        let rows = render_blocks(blocks.as_slice(), &render);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].len(), 100_000);
+    }
 }
@@ -0,0 +1,138 @@
+//! Segment styles: body and start/end caps.
+
+use itertools::repeat_n;
+use std::iter;
+
+/// Built-in look of segments.
+///
+/// ```
+/// use tbl::SegmentStyle;
+/// assert_eq!(SegmentStyle::Plain.render(6, None), "======");
+/// assert_eq!(SegmentStyle::Ascii.render(6, None), "[====]");
+/// assert_eq!(SegmentStyle::Box.render(6, None), "├────┤");
+/// assert_eq!(SegmentStyle::Rounded.render(6, None), "╶────╴");
+/// assert_eq!(SegmentStyle::Solid.render(6, None), "▐████▌");
+/// assert_eq!(SegmentStyle::Ascii.render(6, Some('▒')), "[▒▒▒▒]");
+/// assert_eq!(SegmentStyle::Ascii.render(1, None), "|");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegmentStyle {
+    /// `======`
+    Plain,
+    /// `[====]`
+    Ascii,
+    /// `├────┤`
+    Box,
+    /// `╶────╴`
+    Rounded,
+    /// `▐████▌`
+    Solid,
+}
+
+struct Caps {
+    start: char,
+    end: char,
+    /// a one column segment
+    single: char,
+}
+
+impl SegmentStyle {
+    fn caps(&self) -> Option<Caps> {
+        match self {
+            SegmentStyle::Plain => None,
+            SegmentStyle::Ascii => Some(Caps {
+                start: '[',
+                end: ']',
+                single: '|',
+            }),
+            SegmentStyle::Box => Some(Caps {
+                start: '├',
+                end: '┤',
+                single: '│',
+            }),
+            SegmentStyle::Rounded => Some(Caps {
+                start: '╶',
+                end: '╴',
+                single: '•',
+            }),
+            SegmentStyle::Solid => Some(Caps {
+                start: '▐',
+                end: '▌',
+                single: '█',
+            }),
+        }
+    }
+
+    pub(crate) fn body(&self) -> char {
+        match self {
+            SegmentStyle::Plain | SegmentStyle::Ascii => '=',
+            SegmentStyle::Box | SegmentStyle::Rounded => '─',
+            SegmentStyle::Solid => '█',
+        }
+    }
+
+    fn vertical_caps(&self) -> Option<Caps> {
+        match self {
+            SegmentStyle::Plain => None,
+            SegmentStyle::Ascii => Some(Caps {
+                start: '-',
+                end: '-',
+                single: '-',
+            }),
+            SegmentStyle::Box => Some(Caps {
+                start: '┬',
+                end: '┴',
+                single: '─',
+            }),
+            SegmentStyle::Rounded => Some(Caps {
+                start: '╷',
+                end: '╵',
+                single: '•',
+            }),
+            SegmentStyle::Solid => Some(Caps {
+                start: '▄',
+                end: '▀',
+                single: '█',
+            }),
+        }
+    }
+
+    /// Body of vertical segments.
+    pub(crate) fn vertical_body(&self) -> char {
+        match self {
+            SegmentStyle::Plain => '=',
+            SegmentStyle::Ascii => '|',
+            SegmentStyle::Box | SegmentStyle::Rounded => '│',
+            SegmentStyle::Solid => '█',
+        }
+    }
+
+    /// Character of the row `row` of a vertical segment `rows` high: the caps on the first and
+    /// last rows, `body` otherwise.
+    pub(crate) fn vertical_row(&self, row: usize, rows: usize, body: char) -> char {
+        match (self.vertical_caps(), rows) {
+            (None, _) => body,
+            (Some(caps), 1) => caps.single,
+            (Some(caps), _) if row == 0 => caps.start,
+            (Some(caps), _) if row + 1 == rows => caps.end,
+            _ => body,
+        }
+    }
+
+    /// Render a segment `length` columns wide.
+    ///
+    /// `body` overrides the style body character (e.g. a pattern fill).
+    pub fn render(&self, length: usize, body: Option<char>) -> String {
+        let body = body.unwrap_or_else(|| self.body());
+        match (self.caps(), length) {
+            (_, 0) => String::new(),
+            (None, _) => repeat_n(body, length).collect(),
+            (Some(caps), 1) => caps.single.to_string(),
+            (Some(caps), _) => iter::once(caps.start)
+                .chain(repeat_n(body, length - 2))
+                .chain(iter::once(caps.end))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Crate-native styling: colors, text attributes and styled spans.
+//!
+//! Block renderers may return styled spans (see `RenderBlock::StyledBlock`) instead of
+//! strings with embedded escape codes, widths are then computed from the plain text.
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// An entry of the 256 colors palette.
+    Indexed(u8),
+    /// A 24 bits color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// 24 bits value, standard colors use xterm defaults.
+    ///
+    /// ```
+    /// use tbl::Color;
+    /// assert_eq!(Color::Rgb(3, 169, 244).rgb(), (3, 169, 244));
+    /// assert_eq!(Color::Indexed(39).rgb(), (0, 175, 255));
+    /// assert_eq!(Color::BrightWhite.rgb(), (255, 255, 255));
+    /// ```
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        crate::ansi::rgb(*self)
+    }
+}
+
+/// Foreground, background and text attributes.
+///
+/// ```
+/// use tbl::{Color, Style};
+/// let style = Style::new().fg(Color::White).bg(Color::Rgb(3, 169, 244)).bold();
+/// assert_eq!(style.bg, Some(Color::Rgb(3, 169, 244)));
+/// assert!(style.bold);
+/// assert!(Style::new().is_plain());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub dim: bool,
+}
+
+impl Style {
+    /// Returns a plain style: no colors, no attributes.
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    /// `true` if this style has no effect ie text is rendered as is.
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+}
+
+/// A piece of text rendered with a single style.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    /// Returns an unstyled span.
+    pub fn raw<S: Into<String>>(text: S) -> Self {
+        Span {
+            text: text.into(),
+            style: Style::default(),
+        }
+    }
+
+    pub fn styled<S: Into<String>>(text: S, style: Style) -> Self {
+        Span {
+            text: text.into(),
+            style,
+        }
+    }
+
+    /// Display width (characters) of the span text.
+    pub fn width(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+/// A single output line made of styled spans.
+pub type Line = Vec<Span>;
+
+/// Display width (characters) of a line.
+pub fn width(line: &[Span]) -> usize {
+    line.iter().map(Span::width).sum()
+}
+
+/// Line text without any styling.
+pub fn plain(line: &[Span]) -> String {
+    line.iter().map(|span| span.text.as_str()).collect()
+}
+
+/// Truncate or pad `line` so that its width is exactly `width`.
+///
+/// Padding spaces keep the background color of the last span.
+///
+/// ```
+/// use tbl::{style, Span, Style};
+/// let line = vec![Span::styled("hello", Style::new().bold()), Span::raw(" world")];
+/// assert_eq!(style::plain(&style::fit(&line, 8)), "hello wo");
+/// assert_eq!(style::plain(&style::fit(&line, 13)), "hello world  ");
+/// ```
+pub fn fit(line: &[Span], width: usize) -> Line {
+    let mut remaining = width;
+    let mut fitted: Line = vec![];
+    for span in line {
+        if remaining == 0 {
+            break;
+        }
+        let text: String = span.text.chars().take(remaining).collect();
+        remaining -= text.chars().count();
+        fitted.push(Span::styled(text, span.style));
+    }
+    if remaining > 0 {
+        let background = line.last().and_then(|span| span.style.bg);
+        let style = Style {
+            bg: background,
+            ..Style::default()
+        };
+        fitted.push(Span::styled(" ".repeat(remaining), style));
+    }
+    fitted
+}
@@ -0,0 +1,178 @@
+//! SVG backend.
+
+use crate::layout::{Layout, Segment};
+use crate::style::Color;
+use std::fmt::Write;
+
+/// Width of a timeline column (px).
+const COLUMN_WIDTH: f64 = 8.;
+const LANE_HEIGHT: f64 = 20.;
+const LANE_GAP: f64 = 4.;
+const HEADER_HEIGHT: f64 = 20.;
+const AXIS_HEIGHT: f64 = 24.;
+const TICK_LENGTH: f64 = 4.;
+const MARGIN: f64 = 10.;
+const FONT_SIZE: f64 = 12.;
+/// Approximate width of a character (px), used to truncate labels.
+const CHAR_WIDTH: f64 = 7.2;
+const TARGET_TICKS: f64 = 8.;
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub(crate) fn hex(color: Color) -> String {
+    let (r, g, b) = color.rgb();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Black or white, whichever is more readable on `background`.
+pub(crate) fn text_color(background: Color) -> Color {
+    let (r, g, b) = background.rgb();
+    let luminance = 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b);
+    if luminance > 140. {
+        Color::Black
+    } else {
+        Color::BrightWhite
+    }
+}
+
+/// A "nice" step (1, 2 or 5 times a power of 10) splitting `range` in about `target` ticks.
+fn nice_step(range: f64, target: f64) -> f64 {
+    let raw = range / target;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let normalized = raw / magnitude;
+    let nice = [1., 2., 5.]
+        .iter()
+        .find(|&&n| normalized <= n)
+        .cloned()
+        .unwrap_or(10.);
+    nice * magnitude
+}
+
+/// Axis ticks within `boundaries`.
+pub(crate) fn ticks(boundaries: (f64, f64)) -> Vec<f64> {
+    let (min, max) = boundaries;
+    if max <= min || !(max - min).is_finite() {
+        return vec![];
+    }
+    let step = nice_step(max - min, TARGET_TICKS);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+pub(crate) fn render_svg<L>(
+    layout: &Layout<L>,
+    length: usize,
+    label: &dyn Fn(&L) -> String,
+    bound: &dyn Fn(f64) -> String,
+    color: &dyn Fn(&Segment<L>) -> Color,
+) -> String {
+    let x = |v: f64| MARGIN + layout.position(v, length) * COLUMN_WIDTH;
+    let width = 2. * MARGIN + length as f64 * COLUMN_WIDTH;
+    let mut body = String::new();
+    let mut y = MARGIN;
+    for group in layout.groups.iter() {
+        body.push_str("<g class=\"group\">\n");
+        if let Some(name) = &group.name {
+            let _ = writeln!(
+                body,
+                "<text class=\"group-header\" x=\"{:.2}\" y=\"{:.2}\" font-weight=\"bold\">{}</text>",
+                MARGIN,
+                y + FONT_SIZE,
+                escape(name)
+            );
+            y += HEADER_HEIGHT;
+        }
+        for lane in group.lanes.iter() {
+            body.push_str("<g class=\"lane\">\n");
+            for segment in lane.segments.iter() {
+                let (a, b) = segment.bounds;
+                let (xa, xb) = (x(a), x(b));
+                let text = segment.label.as_ref().map(label).unwrap_or_default();
+                let fill = color(segment);
+                let _ = writeln!(
+                    body,
+                    "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"><title>{}</title></rect>",
+                    xa,
+                    y,
+                    xb - xa,
+                    LANE_HEIGHT,
+                    hex(fill),
+                    escape(&text)
+                );
+                let fitting = ((xb - xa - 4.) / CHAR_WIDTH).floor().max(0.) as usize;
+                let truncated: String = text.chars().take(fitting).collect();
+                if !truncated.is_empty() {
+                    let _ = writeln!(
+                        body,
+                        "<text x=\"{:.2}\" y=\"{:.2}\" fill=\"{}\">{}</text>",
+                        xa + 2.,
+                        y + (LANE_HEIGHT + FONT_SIZE) / 2. - 2.,
+                        hex(text_color(fill)),
+                        escape(&truncated)
+                    );
+                }
+            }
+            body.push_str("</g>\n");
+            y += LANE_HEIGHT + LANE_GAP;
+        }
+        body.push_str("</g>\n");
+    }
+    body.push_str("<g class=\"axis\">\n");
+    let _ = writeln!(
+        body,
+        "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"black\"/>",
+        MARGIN,
+        y,
+        width - MARGIN,
+        y
+    );
+    if let Some(boundaries) = layout.boundaries {
+        for tick in ticks(boundaries) {
+            let _ = writeln!(
+                body,
+                "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"black\"/>",
+                x(tick),
+                y,
+                x(tick),
+                y + TICK_LENGTH
+            );
+            let _ = writeln!(
+                body,
+                "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\">{}</text>",
+                x(tick),
+                y + TICK_LENGTH + FONT_SIZE,
+                escape(&bound(tick))
+            );
+        }
+    }
+    body.push_str("</g>\n");
+    let height = y + AXIS_HEIGHT + MARGIN;
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.2}\" height=\"{:.2}\" viewBox=\"0 0 {:.2} {:.2}\" font-family=\"monospace\" font-size=\"{}\">\n{}</svg>\n",
+        width, height, width, height, FONT_SIZE, body
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::svg::{escape, ticks};
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(ticks((0., 10.)), vec![0., 2., 4., 6., 8., 10.]);
+        assert_eq!(ticks((3., 47.)), vec![10., 20., 30., 40.]);
+        assert!(ticks((1., 1.)).is_empty());
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
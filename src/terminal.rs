@@ -0,0 +1,34 @@
+//! Terminal width detection.
+
+use crate::rendering::DEFAULT_LENGTH;
+use std::env;
+use terminal_size::{terminal_size, Width};
+
+/// Width (columns) of the terminal attached to stdout.
+///
+/// see `terminal::width_from`
+pub fn width() -> usize {
+    width_from(
+        terminal_size().map(|(Width(width), _)| width as usize),
+        env::var("COLUMNS").ok().as_deref(),
+    )
+}
+
+/// Width from the size of the stdout TTY (if any), then from `COLUMNS`, then `DEFAULT_LENGTH` (90).
+///
+/// ```
+/// use tbl::terminal;
+/// assert_eq!(terminal::width_from(Some(120), Some("80")), 120);
+/// assert_eq!(terminal::width_from(None, Some("80")), 80);
+/// assert_eq!(terminal::width_from(None, Some("wide")), 90);
+/// assert_eq!(terminal::width_from(Some(0), None), 90);
+/// ```
+pub fn width_from(tty: Option<usize>, columns: Option<&str>) -> usize {
+    tty.filter(|&width| width > 0)
+        .or_else(|| {
+            columns
+                .and_then(|columns| columns.trim().parse().ok())
+                .filter(|&width: &usize| width > 0)
+        })
+        .unwrap_or(DEFAULT_LENGTH)
+}
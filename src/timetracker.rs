@@ -0,0 +1,358 @@
+//! Time tracker exports import: timewarrior JSON, watson JSON and toggl CSV.
+//!
+//! Entries are intervals in seconds since the Unix epoch, running entries have no end.
+
+use crate::Bound;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use csv::{ReaderBuilder, StringRecord, Trim};
+use serde_json::Value;
+use thiserror::Error;
+
+/// A time tracker export format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tracker {
+    /// `timew export`: the first tag is the group, other tags the label.
+    Timewarrior,
+    /// `watson log --json` or watson's `frames` file: the project is the group, tags the label.
+    Watson,
+    /// toggl detailed report CSV: the project is the group, the description the label.
+    Toggl,
+}
+
+/// A tracked time entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    /// Tags or description, the group if empty.
+    pub label: String,
+    /// Project (first tag for timewarrior), if any.
+    pub group: Option<String>,
+    pub tags: Vec<String>,
+    /// In seconds since the Unix epoch.
+    pub start: f64,
+    /// `None` for running entries.
+    pub end: Option<f64>,
+}
+
+impl TimeEntry {
+    /// Start and end, running entries end at `now` (at their start if `now` is earlier).
+    pub fn bounds(&self, now: f64) -> Bound {
+        (self.start, self.end.unwrap_or_else(|| now.max(self.start)))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TrackerError {
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Csv(#[from] csv::Error),
+    /// Entries are numbered from 1.
+    #[error("entry {entry}: {message}")]
+    Parse { entry: usize, message: String },
+    #[error("unknown time tracker export")]
+    Unknown,
+}
+
+fn error(entry: usize, message: String) -> TrackerError {
+    TrackerError::Parse { entry, message }
+}
+
+/// Guess the format of an export: timewarrior entries start at `%Y%m%dT%H%M%SZ` times, watson
+/// log entries start and stop at RFC 3339 times and watson frames are
+/// `[start, stop, project, id, tags, updated]` arrays.
+///
+/// ```
+/// use tbl::timetracker::{detect, Tracker};
+/// assert_eq!(detect(r#"[{"id": 1, "start": "20240501T100000Z", "tags": ["a"]}]"#), Some(Tracker::Timewarrior));
+/// assert_eq!(detect(r#"[{"project": "a", "start": "2024-05-01T10:00:00+02:00", "stop": null}]"#), Some(Tracker::Watson));
+/// assert_eq!(detect("Description,Project,Start date,Start time,End date,End time"), Some(Tracker::Toggl));
+/// assert_eq!(detect("0,1,a"), None);
+/// assert_eq!(detect(r#"[{"start": 0, "end": 5, "label": "a"}]"#), None);
+/// ```
+pub fn detect(text: &str) -> Option<Tracker> {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if !text.starts_with('[') {
+        let header = text.lines().next().unwrap_or_default();
+        return if header.contains("Start date") && header.contains("Start time") {
+            Some(Tracker::Toggl)
+        } else {
+            None
+        };
+    }
+    let entries: Vec<Value> = serde_json::from_str(text).ok()?;
+    let timewarrior = |time: &Value| {
+        time.as_str()
+            .is_some_and(|time| NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%SZ").is_ok())
+    };
+    let rfc3339 = |time: &Value| {
+        time.as_str()
+            .is_some_and(|time| DateTime::parse_from_rfc3339(time).is_ok())
+    };
+    match entries.first()? {
+        // frames: `[start, stop, project, id, tags, updated]`
+        Value::Array(frame)
+            if frame.len() >= 5
+                && frame[0].is_number()
+                && frame[1].is_number()
+                && frame[2].is_string()
+                && frame[4].is_array() =>
+        {
+            Some(Tracker::Watson)
+        }
+        // running entries stop at `null`
+        Value::Object(entry)
+            if entry.get("start").is_some_and(rfc3339)
+                && entry
+                    .get("stop")
+                    .is_some_and(|stop| stop.is_null() || rfc3339(stop)) =>
+        {
+            Some(Tracker::Watson)
+        }
+        Value::Object(entry) if entry.get("start").is_some_and(timewarrior) => {
+            Some(Tracker::Timewarrior)
+        }
+        _ => None,
+    }
+}
+
+/// Entries of an export, sorted by start.
+pub fn parse(text: &str, tracker: Tracker) -> Result<Vec<TimeEntry>, TrackerError> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut entries = match tracker {
+        Tracker::Timewarrior => parse_timewarrior(text)?,
+        Tracker::Watson => parse_watson(text)?,
+        Tracker::Toggl => parse_toggl(text)?,
+    };
+    entries.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(entries)
+}
+
+/// Entries of an export in a detected format, see `detect`.
+///
+/// ```
+/// use tbl::timetracker;
+/// let entries = timetracker::parse_detected(r#"[
+///     {"id": 2, "start": "20240501T100000Z", "end": "20240501T113000Z", "tags": ["tbl", "review", "docs"]},
+///     {"id": 1, "start": "20240501T120000Z", "tags": ["lunch"]}
+/// ]"#).unwrap();
+/// assert_eq!(entries[0].group.as_deref(), Some("tbl"));
+/// assert_eq!(entries[0].label, "review, docs");
+/// assert_eq!(entries[0].bounds(0.), (1_714_557_600., 1_714_563_000.));
+/// assert_eq!(entries[1].label, "lunch");
+/// assert_eq!(entries[1].end, None); // running
+/// assert_eq!(entries[1].bounds(1_714_566_000.).1, 1_714_566_000.);
+/// ```
+pub fn parse_detected(text: &str) -> Result<Vec<TimeEntry>, TrackerError> {
+    parse(text, detect(text).ok_or(TrackerError::Unknown)?)
+}
+
+fn tags(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A time entry labelled by `label`, by its group if empty.
+fn entry(
+    label: String,
+    group: Option<String>,
+    tags: Vec<String>,
+    bounds: (f64, Option<f64>),
+) -> TimeEntry {
+    let label = match (label.is_empty(), &group) {
+        (true, Some(group)) => group.clone(),
+        _ => label,
+    };
+    TimeEntry {
+        label,
+        group,
+        tags,
+        start: bounds.0,
+        end: bounds.1,
+    }
+}
+
+fn parse_timewarrior(text: &str) -> Result<Vec<TimeEntry>, TrackerError> {
+    let entries: Vec<Value> = serde_json::from_str(text)?;
+    let time = |index: usize, value: Option<&Value>| -> Result<Option<f64>, TrackerError> {
+        value
+            .and_then(Value::as_str)
+            .map(|time| {
+                NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%SZ")
+                    .map(|time| time.and_utc().timestamp() as f64)
+                    .map_err(|_| error(index, format!("`{}` is not a date", time)))
+            })
+            .transpose()
+    };
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let start = time(i + 1, value.get("start"))?
+                .ok_or_else(|| error(i + 1, "no start".to_string()))?;
+            let end = time(i + 1, value.get("end"))?;
+            let tags = tags(value.get("tags"));
+            let group = tags.first().cloned();
+            let label = tags.iter().skip(1).cloned().collect::<Vec<_>>().join(", ");
+            Ok(entry(label, group, tags, (start, end)))
+        })
+        .collect()
+}
+
+fn parse_watson(text: &str) -> Result<Vec<TimeEntry>, TrackerError> {
+    let entries: Vec<Value> = serde_json::from_str(text)?;
+    let time = |index: usize, value: Option<&Value>| -> Result<Option<f64>, TrackerError> {
+        match value {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::Number(seconds)) => Ok(seconds.as_f64()),
+            Some(Value::String(time)) => DateTime::parse_from_rfc3339(time)
+                .map(|time| Some(time.timestamp() as f64))
+                .map_err(|_| error(index, format!("`{}` is not a date", time))),
+            Some(other) => Err(error(index, format!("`{}` is not a date", other))),
+        }
+    };
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            // log entries or frames
+            let (start, stop, project, tags) = match value {
+                Value::Array(frame) => (frame.first(), frame.get(1), frame.get(2), frame.get(4)),
+                entry => (
+                    entry.get("start"),
+                    entry.get("stop"),
+                    entry.get("project"),
+                    entry.get("tags"),
+                ),
+            };
+            let start = time(i + 1, start)?.ok_or_else(|| error(i + 1, "no start".to_string()))?;
+            let end = time(i + 1, stop)?;
+            let tags = self::tags(tags);
+            let group = project.and_then(Value::as_str).map(str::to_string);
+            Ok(entry(tags.join(", "), group, tags, (start, end)))
+        })
+        .collect()
+}
+
+fn parse_toggl(text: &str) -> Result<Vec<TimeEntry>, TrackerError> {
+    let mut reader = ReaderBuilder::new()
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(text.as_bytes());
+    let header = reader.headers()?.clone();
+    let column = |name: &str| header.iter().position(|field| field == name);
+    let field = |record: &StringRecord, name: &str| -> String {
+        column(name)
+            .and_then(|index| record.get(index))
+            .unwrap_or_default()
+            .to_string()
+    };
+    // local times without time zone: UTC
+    let time = |index: usize, date: String, time: String| -> Result<Option<f64>, TrackerError> {
+        if date.is_empty() {
+            return Ok(None);
+        }
+        let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| error(index, format!("`{}` is not a date", date)))?;
+        let time = NaiveTime::parse_from_str(&time, "%H:%M:%S")
+            .map_err(|_| error(index, format!("`{}` is not a time", time)))?;
+        Ok(Some(date.and_time(time).and_utc().timestamp() as f64))
+    };
+    reader
+        .records()
+        .enumerate()
+        .map(|(i, record)| {
+            let record = record?;
+            let start = time(
+                i + 1,
+                field(&record, "Start date"),
+                field(&record, "Start time"),
+            )?
+            .ok_or_else(|| error(i + 1, "no start".to_string()))?;
+            let end = time(
+                i + 1,
+                field(&record, "End date"),
+                field(&record, "End time"),
+            )?;
+            let project = field(&record, "Project");
+            let tags: Vec<String> = field(&record, "Tags")
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect();
+            let group = Some(project).filter(|project| !project.is_empty());
+            Ok(entry(
+                field(&record, "Description"),
+                group,
+                tags,
+                (start, end),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::timetracker::{detect, parse, Tracker, TrackerError};
+
+    // 2024-05-01T10:00:00Z
+    const START: f64 = 1_714_557_600.;
+
+    #[test]
+    fn test_watson() {
+        let log = r#"[
+            {"id": "b", "project": "tbl", "start": "2024-05-01T13:00:00+02:00", "stop": "2024-05-01T14:00:00+02:00", "tags": ["docs", "review"]},
+            {"id": "a", "project": "mail", "start": "2024-05-01T10:00:00+00:00", "stop": null, "tags": []}
+        ]"#;
+        let entries = parse(log, Tracker::Watson).unwrap();
+        assert_eq!(entries[0].label, "mail");
+        assert_eq!(entries[0].end, None);
+        assert_eq!(entries[1].label, "docs, review");
+        assert_eq!(entries[1].group.as_deref(), Some("tbl"));
+        assert_eq!(entries[1].bounds(0.), (START + 3600., START + 7200.));
+
+        let frames = r#"[[1714557600, 1714561200, "tbl", "a", ["docs"], 1714561200]]"#;
+        assert_eq!(detect(frames), Some(Tracker::Watson));
+        let entries = parse(frames, Tracker::Watson).unwrap();
+        assert_eq!(entries[0].bounds(0.), (START, START + 3600.));
+        assert_eq!(entries[0].label, "docs");
+    }
+
+    #[test]
+    fn test_toggl() {
+        let csv = "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags\n\
+                   Ann,ann@example.com,ACME,Website,,Fix header,Yes,2024-05-01,10:00:00,2024-05-01,10:45:00,00:45:00,\"design, css\"\n\
+                   Ann,ann@example.com,,,,,No,2024-05-01,11:00:00,,,,\n";
+        assert_eq!(detect(csv), Some(Tracker::Toggl));
+        let entries = parse(csv, Tracker::Toggl).unwrap();
+        assert_eq!(entries[0].label, "Fix header");
+        assert_eq!(entries[0].group.as_deref(), Some("Website"));
+        assert_eq!(entries[0].tags, vec!["design", "css"]);
+        assert_eq!(entries[0].bounds(0.), (START, START + 2700.));
+        assert_eq!(entries[1].label, "");
+        assert_eq!(entries[1].end, None);
+        assert_eq!(entries[1].bounds(0.), (START + 3600., START + 3600.));
+    }
+
+    #[test]
+    fn test_errors() {
+        // frames are numbered like log entries
+        let text = r#"[[1714557600, 1714561200, "tbl"], [true, null, "tbl"]]"#;
+        let error = parse(text, Tracker::Watson).unwrap_err();
+        assert_eq!(error.to_string(), "entry 2: `true` is not a date");
+        // toggl entries are numbered from the first record, not from the header
+        let csv =
+            "Description,Start date,Start time\nReview,2024-05-01,10:00:00\nx,2024-05-01,noon\n";
+        assert!(matches!(
+            parse(csv, Tracker::Toggl),
+            Err(TrackerError::Parse { entry: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+//! Chrome Trace Event (and Perfetto JSON) import.
+//!
+//! Complete events (`ph: "X"`) and `B`/`E` pairs become intervals, in microseconds like
+//! trace timestamps, grouped by process and thread.
+
+use crate::Bound;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// An interval read from a trace: a complete event or a matched `B`/`E` pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub name: String,
+    /// Event categories (`cat`), if any.
+    pub category: Option<String>,
+    /// Process name (`process_name` metadata event) or `pid`.
+    pub process: String,
+    /// Thread name (`thread_name` metadata event) or `tid`.
+    pub thread: String,
+    /// Start and end, in microseconds.
+    pub bounds: Bound,
+}
+
+impl TraceEvent {
+    /// Swimlane of the event: `process/thread`, see `Renderer::with_groups`.
+    pub fn group(&self) -> String {
+        format!("{}/{}", self.process, self.thread)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TraceError {
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("not a trace: expected an array of events or an object with `traceEvents`")]
+    Format,
+}
+
+#[derive(Deserialize)]
+struct RawEvent {
+    #[serde(default)]
+    name: String,
+    cat: Option<String>,
+    #[serde(default)]
+    ph: String,
+    ts: Option<f64>,
+    dur: Option<f64>,
+    #[serde(default)]
+    pid: Value,
+    #[serde(default)]
+    tid: Value,
+    #[serde(default)]
+    args: Value,
+}
+
+fn id(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn events(text: &str) -> Result<Vec<RawEvent>, TraceError> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        // the array format may omit the closing bracket
+        Err(e) if text.trim_start().starts_with('[') => {
+            let text = text.trim_end().trim_end_matches(',');
+            serde_json::from_str(&format!("{}]", text)).map_err(|_| e)?
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let events = match value {
+        Value::Array(events) => events,
+        Value::Object(mut trace) => match trace.remove("traceEvents") {
+            Some(Value::Array(events)) => events,
+            _ => return Err(TraceError::Format),
+        },
+        _ => return Err(TraceError::Format),
+    };
+    // events which are not objects are ignored
+    Ok(events
+        .into_iter()
+        .filter_map(|event| serde_json::from_value(event).ok())
+        .collect())
+}
+
+/// Intervals of a Chrome Trace Event JSON trace, sorted by start.
+///
+/// `E` events close the latest open `B` event of the same thread, unmatched events are ignored,
+/// as are other phases (instant, counter, async...).
+///
+/// ```
+/// use tbl::trace;
+/// let events = trace::parse(r#"{"traceEvents": [
+///     {"name": "process_name", "ph": "M", "pid": 1, "args": {"name": "server"}},
+///     {"name": "parse", "ph": "X", "ts": 10, "dur": 5, "pid": 1, "tid": 7},
+///     {"name": "main", "ph": "B", "ts": 0, "pid": 1, "tid": 7},
+///     {"ph": "E", "ts": 20, "pid": 1, "tid": 7}
+/// ]}"#).unwrap();
+/// assert_eq!(events.len(), 2);
+/// assert_eq!((events[0].name.as_str(), events[0].bounds), ("main", (0., 20.)));
+/// assert_eq!((events[1].name.as_str(), events[1].bounds), ("parse", (10., 15.)));
+/// assert_eq!(events[1].group(), "server/7");
+/// ```
+pub fn parse(text: &str) -> Result<Vec<TraceEvent>, TraceError> {
+    let events = events(text)?;
+    let name = |event: &RawEvent| {
+        event
+            .args
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let processes: HashMap<String, String> = events
+        .iter()
+        .filter(|event| event.ph == "M" && event.name == "process_name")
+        .filter_map(|event| Some((id(&event.pid), name(event)?)))
+        .collect();
+    let threads: HashMap<(String, String), String> = events
+        .iter()
+        .filter(|event| event.ph == "M" && event.name == "thread_name")
+        .filter_map(|event| Some(((id(&event.pid), id(&event.tid)), name(event)?)))
+        .collect();
+    let event = |raw: &RawEvent, bounds: Bound| {
+        let (pid, tid) = (id(&raw.pid), id(&raw.tid));
+        TraceEvent {
+            name: raw.name.clone(),
+            category: raw.cat.clone(),
+            process: processes.get(&pid).cloned().unwrap_or_else(|| pid.clone()),
+            thread: threads.get(&(pid, tid.clone())).cloned().unwrap_or(tid),
+            bounds,
+        }
+    };
+
+    let mut parsed = vec![];
+    // open `B` events by thread
+    let mut open: HashMap<(String, String), Vec<&RawEvent>> = HashMap::new();
+    for raw in events.iter() {
+        let thread = (id(&raw.pid), id(&raw.tid));
+        match (raw.ph.as_str(), raw.ts) {
+            ("X", Some(ts)) => parsed.push(event(raw, (ts, ts + raw.dur.unwrap_or(0.)))),
+            ("B", Some(_)) => open.entry(thread).or_default().push(raw),
+            ("E", Some(ts)) => {
+                if let Some(begin) = open.get_mut(&thread).and_then(Vec::pop) {
+                    parsed.push(event(begin, (begin.ts.unwrap_or(ts), ts)));
+                }
+            }
+            _ => {}
+        }
+    }
+    parsed.sort_by(|a, b| a.bounds.0.total_cmp(&b.bounds.0));
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::trace::{parse, TraceError};
+
+    #[test]
+    fn test_nested_pairs() {
+        let events = parse(
+            r#"[
+            {"name": "outer", "ph": "B", "ts": 0, "pid": 1, "tid": 1},
+            {"name": "other thread", "ph": "B", "ts": 1, "pid": 1, "tid": 2},
+            {"name": "inner", "ph": "B", "ts": 2, "pid": 1, "tid": 1},
+            {"ph": "E", "ts": 3, "pid": 1, "tid": 1},
+            {"ph": "E", "ts": 4, "pid": 1, "tid": 1},
+            {"name": "unmatched", "ph": "B", "ts": 5, "pid": 1, "tid": 1},
+            {"name": "instant", "ph": "i", "ts": 6, "pid": 1, "tid": 1},"#,
+        )
+        .unwrap();
+        let names: Vec<(&str, (f64, f64))> =
+            events.iter().map(|e| (e.name.as_str(), e.bounds)).collect();
+        assert_eq!(names, vec![("outer", (0., 4.)), ("inner", (2., 3.))]);
+    }
+
+    #[test]
+    fn test_thread_names() {
+        let events = parse(
+            r#"[
+            {"name": "thread_name", "ph": "M", "pid": "p", "tid": 2, "args": {"name": "worker"}},
+            {"name": "a", "cat": "io", "ph": "X", "ts": 1.5, "dur": 2, "pid": "p", "tid": 2},
+            {"name": "b", "ph": "X", "ts": 0, "pid": "p", "tid": 3}
+        ]"#,
+        )
+        .unwrap();
+        assert_eq!(events[0].group(), "p/3");
+        assert_eq!(events[0].bounds, (0., 0.));
+        assert_eq!(events[1].group(), "p/worker");
+        assert_eq!(events[1].category.as_deref(), Some("io"));
+        assert!(matches!(
+            parse(r#"{"events": []}"#),
+            Err(TraceError::Format)
+        ));
+        assert!(matches!(parse("{"), Err(TraceError::Json(_))));
+    }
+}
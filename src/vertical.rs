@@ -0,0 +1,112 @@
+//! Vertical orientation: time runs down the rows, lanes are rendered as columns.
+
+use crate::style::{fit, Line, Span};
+use itertools::repeat_n;
+
+/// Width of a lane column (characters).
+pub(crate) const DEFAULT_LANE_WIDTH: usize = 12;
+/// Between lane columns.
+const GAP: &str = " ";
+/// First row of a segment touching a previous segment with the same fill.
+const SEPARATOR: char = '─';
+
+/// Timeline orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// Time runs left to right, lanes are rows.
+    Horizontal,
+    /// Time runs top to bottom, lanes are columns.
+    Vertical,
+}
+
+/// Rows of a vertical segment: the label (if any) on the first row, then `fill` (of each row).
+pub(crate) fn segment_rows(
+    rows: usize,
+    width: usize,
+    label: Option<&str>,
+    fill: &dyn Fn(usize) -> char,
+    separated: bool,
+) -> Vec<String> {
+    (0..rows)
+        .map(|row| {
+            let fill = if row == 0 && separated && rows > 1 {
+                SEPARATOR
+            } else {
+                fill(row)
+            };
+            let text: String = match label {
+                Some(label) if row == 0 => label.chars().take(width).collect(),
+                _ => String::new(),
+            };
+            let padding = repeat_n(fill, width - text.chars().count());
+            text.chars().chain(padding).collect()
+        })
+        .collect()
+}
+
+/// Join lane columns row by row, `headers` (group name, number of lanes) are rendered above.
+pub(crate) fn join_columns(
+    columns: &[Vec<Line>],
+    width: usize,
+    headers: Option<Vec<(String, usize)>>,
+) -> Vec<Line> {
+    let rows = columns.iter().map(Vec::len).max().unwrap_or_default();
+    let header = headers.map(|headers| {
+        headers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (name, lanes))| {
+                let group_width = lanes * width + lanes.saturating_sub(1) * GAP.len();
+                let gap = if i == 0 { None } else { Some(Span::raw(GAP)) };
+                gap.into_iter()
+                    .chain(fit(&[Span::raw(name.clone())], group_width))
+            })
+            .collect::<Line>()
+    });
+    let body = (0..rows).map(|row| {
+        columns
+            .iter()
+            .enumerate()
+            .flat_map(|(i, column)| {
+                let gap = if i == 0 { None } else { Some(Span::raw(GAP)) };
+                let cell = column
+                    .get(row)
+                    .cloned()
+                    .unwrap_or_else(|| vec![Span::raw(" ".repeat(width))]);
+                gap.into_iter().chain(cell)
+            })
+            .collect::<Line>()
+    });
+    header.into_iter().chain(body).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::style::{plain, Span};
+    use crate::vertical::{join_columns, segment_rows};
+
+    #[test]
+    fn test_segment_rows() {
+        assert_eq!(
+            segment_rows(3, 4, Some("label"), &|_| '=', false),
+            vec!["labe", "====", "===="]
+        );
+        assert_eq!(
+            segment_rows(2, 4, None, &|_| '=', true),
+            vec!["────", "===="]
+        );
+        assert!(segment_rows(0, 4, None, &|_| '=', false).is_empty());
+    }
+
+    #[test]
+    fn test_join_columns() {
+        let columns = vec![
+            vec![vec![Span::raw("aa")], vec![Span::raw("  ")]],
+            vec![vec![Span::raw("bb")], vec![Span::raw("bb")]],
+        ];
+        let joined = join_columns(columns.as_slice(), 2, Some(vec![("group".to_string(), 2)]));
+        let joined: Vec<String> = joined.iter().map(|line| plain(line)).collect();
+        assert_eq!(joined, vec!["group", "aa bb", "   bb"]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Wrapped layout: the domain is cut into consecutive windows rendered as rows.
+
+use crate::interval::{is_empty, is_finite, TBLInterval};
+use crate::style::{Line, Span};
+use crate::{Block, Bound};
+use std::fmt::Debug;
+
+/// Maximum number of windows.
+pub(crate) const MAX_WINDOWS: usize = 100_000;
+/// Marks a segment continued from the previous row.
+const CONTINUED_LEFT: char = '«';
+/// Marks a segment continued on the next row.
+const CONTINUED_RIGHT: char = '»';
+
+/// How the domain is cut into windows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Wrap {
+    /// Windows of a fixed span (e.g. `3600.` for an hour of timestamps in seconds),
+    /// aligned on multiples of the span. Each row is `length` columns wide.
+    Span(f64),
+    /// Rows of a fixed width (columns), the whole timeline being `length` columns wide.
+    Width(usize),
+}
+
+/// A label of an interval clipped to a window.
+#[derive(Debug, Clone)]
+pub(crate) struct Clipped<L> {
+    pub(crate) label: Option<L>,
+    /// the interval starts before the window
+    left: bool,
+    /// the interval ends after the window
+    right: bool,
+}
+
+/// Windows covering `boundaries` and the width (columns) of each row.
+///
+/// Returns the number of windows if there would be more than `MAX_WINDOWS`.
+pub(crate) fn windows(
+    wrap: Wrap,
+    boundaries: Bound,
+    length: usize,
+) -> Result<(Vec<Bound>, usize), usize> {
+    let (min, max) = boundaries;
+    let (start, span, width) = match wrap {
+        Wrap::Span(span) => ((min / span).floor() * span, span, length),
+        Wrap::Width(width) if length > 0 => {
+            (min, (max - min) * width as f64 / length as f64, width)
+        }
+        Wrap::Width(width) => (min, 0., width),
+    };
+    if !span.is_finite() || span <= 0. || max <= min {
+        return Ok((vec![boundaries], width));
+    }
+    let count = ((max - start) / span).ceil().max(1.);
+    if count > MAX_WINDOWS as f64 {
+        // saturates for huge counts
+        return Err(count as usize);
+    }
+    let count = count as usize;
+    let windows = (0..count)
+        .map(|i| (start + i as f64 * span, start + (i + 1) as f64 * span))
+        .collect();
+    Ok((windows, width))
+}
+
+/// Intervals intersecting `window`, clipped to it.
+pub(crate) fn clip<L: Clone + Debug>(
+    intervals: &[TBLInterval<L>],
+    window: Bound,
+) -> Vec<TBLInterval<Clipped<L>>> {
+    let (wa, wb) = window;
+    intervals
+        .iter()
+        .filter(|interval| is_finite(interval) && !is_empty(interval))
+        .filter(|interval| interval.bounds.0 < wb && interval.bounds.1 > wa)
+        .map(|interval| {
+            let (a, b) = interval.bounds;
+            TBLInterval::new(
+                (a.max(wa), b.min(wb)),
+                Some(Clipped {
+                    label: interval.label.clone(),
+                    left: a < wa,
+                    right: b > wb,
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Blocks without clipping information, and continuation marks (column, mark).
+pub(crate) fn unclip<L: Clone + Debug>(
+    blocks: Vec<Block<Clipped<L>>>,
+) -> (Vec<Block<L>>, Vec<(usize, char)>) {
+    let mut column = 0;
+    let mut marks = vec![];
+    let blocks = blocks
+        .into_iter()
+        .map(|block| match block {
+            Block::Space(length) => {
+                column += length;
+                Block::Space(length)
+            }
+            Block::Segment(length, clipped) => {
+                let clipped = clipped.unwrap_or(Clipped {
+                    label: None,
+                    left: false,
+                    right: false,
+                });
+                if length > 0 && clipped.left {
+                    marks.push((column, CONTINUED_LEFT));
+                }
+                if length > 0 && clipped.right {
+                    marks.push((column + length - 1, CONTINUED_RIGHT));
+                }
+                column += length;
+                Block::Segment(length, clipped.label)
+            }
+        })
+        .collect();
+    (blocks, marks)
+}
+
+/// Replace the character at `column` keeping its style.
+pub(crate) fn overwrite(line: &[Span], column: usize, c: char) -> Line {
+    let mut start = 0;
+    line.iter()
+        .map(|span| {
+            let width = span.width();
+            let overwritten = if start <= column && column < start + width {
+                span.text
+                    .chars()
+                    .enumerate()
+                    .map(|(i, original)| if start + i == column { c } else { original })
+                    .collect()
+            } else {
+                span.text.clone()
+            };
+            start += width;
+            Span::styled(overwritten, span.style)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interval::TBLInterval;
+    use crate::style::{plain, Span, Style};
+    use crate::wrap::{clip, overwrite, unclip, windows, Wrap};
+    use crate::Block;
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(
+            windows(Wrap::Span(10.), (5., 25.), 8),
+            Ok((vec![(0., 10.), (10., 20.), (20., 30.)], 8))
+        );
+        assert_eq!(
+            windows(Wrap::Width(4), (0., 10.), 8),
+            Ok((vec![(0., 5.), (5., 10.)], 4))
+        );
+        assert_eq!(
+            windows(Wrap::Span(0.), (0., 10.), 8),
+            Ok((vec![(0., 10.)], 8))
+        );
+        assert_eq!(windows(Wrap::Span(1e-3), (0., 1e6), 8), Err(1_000_000_000));
+    }
+
+    #[test]
+    fn test_clip_unclip() {
+        let intervals: Vec<TBLInterval<char>> = vec![
+            TBLInterval::new((0., 4.), Some('a')),
+            TBLInterval::new((5., 15.), Some('b')),
+        ];
+        let clipped = clip(intervals.as_slice(), (2., 10.));
+        assert_eq!(clipped.len(), 2);
+        assert_eq!(clipped[0].bounds, (2., 4.));
+        assert_eq!(clipped[1].bounds, (5., 10.));
+        let blocks = clipped
+            .into_iter()
+            .map(|interval| Block::Segment(2, interval.label))
+            .collect();
+        let (_, marks) = unclip(blocks);
+        assert_eq!(marks, vec![(0, '«'), (3, '»')]);
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let line = vec![Span::raw("ab"), Span::styled("cd", Style::new().bold())];
+        let overwritten = overwrite(&line, 2, '«');
+        assert_eq!(plain(&overwritten), "ab«d");
+        assert!(overwritten[1].style.bold);
+    }
+}
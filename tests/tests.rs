@@ -2,7 +2,10 @@ extern crate tbl;
 
 #[cfg(test)]
 mod tests {
-    use tbl::{Bound, Renderer, TBLError};
+    use tbl::{
+        style, Assignment, Block, Bound, CalendarTime, Color, ColorLevel, Orientation, Palette,
+        Patterns, RenderBlock, Renderer, SegmentStyle, Shading, Span, Style, TBLError, Wrap,
+    };
 
     #[test]
     fn test_empty() {
@@ -21,10 +24,7 @@ mod tests {
         let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
             .with_length(8)
             .render();
-        assert!(match rendered {
-            Err(TBLError::Intersection(_, _)) => false,
-            _ => true,
-        })
+        assert!(!matches!(rendered, Err(TBLError::Intersection(_, _))))
     }
 
     #[test]
@@ -74,4 +74,492 @@ mod tests {
             }
         }
     }
+
+    fn render_styled_block(b: &Block<String>) -> RenderBlock {
+        match b {
+            Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+            Block::Segment(_length, label) => RenderBlock::StyledBlock(vec![vec![Span::styled(
+                label.clone().unwrap_or_default(),
+                Style::new().fg(Color::Rgb(255, 152, 0)).bold(),
+            )]]),
+        }
+    }
+
+    #[test]
+    fn test_styled_widths() {
+        let data: Vec<Bound> = vec![(0., 2.), (3., 4.)];
+        let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| {
+            Some("a long label".to_string())
+        })
+        .with_length(8)
+        .with_renderer(&render_styled_block)
+        .render_styled();
+        for line in rendered.unwrap().iter().flatten() {
+            assert_eq!(style::width(line), 8);
+            assert_eq!(style::plain(line), "a lo  a ");
+        }
+    }
+
+    #[test]
+    fn test_styled_ansi() {
+        let data: Vec<Bound> = vec![(0., 1.)];
+        let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| Some("ab".to_string()))
+            .with_length(2)
+            .with_renderer(&render_styled_block)
+            .with_color_level(ColorLevel::TrueColor)
+            .render();
+        for line in rendered.unwrap().iter().flatten() {
+            assert_eq!(line, "\u{1b}[1;38;2;255;152;0mab\u{1b}[0m");
+        }
+    }
+
+    #[test]
+    fn test_styled_monochrome() {
+        let data: Vec<Bound> = vec![(0., 1.)];
+        let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| Some("ab".to_string()))
+            .with_length(2)
+            .with_renderer(&render_styled_block)
+            .with_color_level(ColorLevel::Monochrome)
+            .render();
+        for line in rendered.unwrap().iter().flatten() {
+            assert_eq!(line, "\u{1b}[1mab\u{1b}[0m");
+        }
+    }
+
+    #[test]
+    fn test_palette_hash_is_stable() {
+        let data: Vec<(f64, f64, &str)> = vec![(0., 1., "a"), (1., 2., "b"), (2., 3., "a")];
+        let reversed: Vec<(f64, f64, &str)> = data.iter().rev().cloned().collect();
+        let colors = |data: &[(f64, f64, &str)]| {
+            let mut renderer = Renderer::new(data, &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+                Some(l.to_string())
+            });
+            let renderer = renderer
+                .with_length(3)
+                .with_palette(Palette::colorblind_safe(Assignment::Hash), &|l| l.clone());
+            renderer.render_styled().unwrap()[0][0]
+                .iter()
+                .filter(|span| !span.text.is_empty())
+                .map(|span| span.style.bg)
+                .collect::<Vec<Option<Color>>>()
+        };
+        let colors_a = colors(data.as_slice());
+        assert_eq!(colors_a, colors(reversed.as_slice()));
+        assert_eq!(colors_a[0], colors_a[2]);
+        assert_ne!(colors_a[0], colors_a[1]);
+    }
+
+    #[test]
+    fn test_palette_legend() {
+        let data: Vec<(f64, f64, &str)> = vec![(0., 1., "a"), (1., 2., "b"), (2., 3., "a")];
+        let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        });
+        let renderer = renderer.with_color_level(ColorLevel::Ansi16).with_palette(
+            Palette::new(vec![Color::Red, Color::Blue], Assignment::FirstAppearance),
+            &|l| l.clone(),
+        );
+        assert_eq!(
+            renderer.legend(),
+            vec!["\u{1b}[41m  \u{1b}[0m a", "\u{1b}[44m  \u{1b}[0m b"]
+        );
+    }
+
+    #[test]
+    fn test_palette_and_patterns_keys() {
+        let data: Vec<(f64, f64, &str, &str)> =
+            vec![(0., 1., "a", "x"), (1., 2., "b", "x"), (2., 3., "a", "y")];
+        let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _, _)| (a, b), &|&(
+            _,
+            _,
+            color,
+            pattern,
+        )| {
+            Some((color.to_string(), pattern.to_string()))
+        });
+        let renderer = renderer
+            .with_length(3)
+            .with_color_level(ColorLevel::Monochrome)
+            .with_palette(
+                Palette::new(vec![Color::Red, Color::Blue], Assignment::FirstAppearance),
+                &|(color, _)| color.clone(),
+            )
+            .with_patterns(Patterns::default(), &|(_, pattern)| pattern.clone());
+        let rendered = renderer.render_styled().unwrap();
+        let line = &rendered[0][0];
+        // touching `x` segments, the second one is one column wide: distinguishable
+        assert_eq!(style::plain(line), "█│▓");
+        assert_eq!(line[0].style.bg, Some(Color::Red));
+        assert_eq!(line.last().unwrap().style.bg, Some(Color::Red));
+        assert_eq!(renderer.legend(), vec!["   a", "   b", "██ x", "▓▓ y"]);
+    }
+
+    #[test]
+    fn test_segment_styles_length() {
+        let data: Vec<Bound> = vec![(0., 1.), (1., 2.), (3., 7.)];
+        let styles = vec![
+            SegmentStyle::Plain,
+            SegmentStyle::Ascii,
+            SegmentStyle::Box,
+            SegmentStyle::Rounded,
+            SegmentStyle::Solid,
+        ];
+        for style in styles {
+            for length in 0..50 {
+                let mut renderer =
+                    Renderer::new(data.as_slice(), &|&e| e, &|_| Some("a".to_string()));
+                let rendered = renderer
+                    .with_length(length)
+                    .with_patterns(Patterns::default(), &|l| l.clone())
+                    .with_segment_style(style)
+                    .render();
+                for line in rendered.unwrap().iter().flatten() {
+                    assert_eq!(line.chars().count(), length);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_segment_styles_touching() {
+        let data: Vec<Bound> = vec![(0., 4.), (4., 8.)];
+        let rendered = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+            .with_length(8)
+            .with_segment_style(SegmentStyle::Ascii)
+            .render();
+        for line in rendered.unwrap().iter().flatten() {
+            assert_eq!(line, "[==][==]");
+        }
+    }
+
+    #[test]
+    fn test_groups_share_scale() {
+        let data = vec![(0., 1., "a"), (3., 4., "b"), (0.5, 2., "a")];
+        let rendered = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        })
+        .with_length(8)
+        .with_groups(&|label| Some(label.clone()))
+        .render()
+        .unwrap();
+        assert_eq!(
+            rendered,
+            vec![vec![" ===    "], vec!["==      "], vec!["      =="]]
+        );
+    }
+
+    #[test]
+    fn test_svg_palette_colors() {
+        let data = vec![(0., 1., "a"), (1., 2., "b")];
+        let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        });
+        let svg = renderer
+            .with_palette(
+                Palette::new(
+                    vec![Color::Rgb(255, 0, 0), Color::Blue],
+                    Assignment::FirstAppearance,
+                ),
+                &|l| l.clone(),
+            )
+            .render_svg()
+            .unwrap();
+        assert!(svg.contains(r##"fill="#ff0000"><title>&quot;a&quot;</title>"##));
+        assert!(svg.contains(r##"fill="#0000ee"><title>&quot;b&quot;</title>"##));
+        assert_eq!(svg.matches("<rect").count(), 2);
+    }
+
+    #[test]
+    fn test_html_legend() {
+        let data = vec![(0., 1., "a"), (1., 2., "b"), (2., 3., "a")];
+        let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        });
+        let html = renderer
+            .with_palette(
+                Palette::new(
+                    vec![Color::Rgb(255, 0, 0), Color::Blue],
+                    Assignment::FirstAppearance,
+                ),
+                &|l| l.clone(),
+            )
+            .render_html()
+            .unwrap();
+        assert_eq!(html.matches("class=\"segment\"").count(), 3);
+        assert!(
+            html.contains(r##"<span class="swatch" style="background: #ff0000;"></span>a</div>"##)
+        );
+        assert!(
+            html.contains(r##"<span class="swatch" style="background: #0000ee;"></span>b</div>"##)
+        );
+    }
+
+    fn render_escaped_block(b: &Block<String>) -> RenderBlock {
+        match b {
+            Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+            Block::Segment(length, _) => {
+                RenderBlock::Block(format!("\x1b[44m{}\x1b[0m", "#".repeat(*length)))
+            }
+        }
+    }
+
+    #[test]
+    fn test_markdown_strips_escape_codes() {
+        let data = vec![(0., 2., "a"), (1., 3., "b")];
+        let report = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        })
+        .with_length(6)
+        .with_renderer(&render_escaped_block)
+        .with_groups(&|label| Some(label.to_uppercase()))
+        .with_label_format(&|label| label.clone())
+        .render_markdown()
+        .unwrap();
+        assert!(!report.contains('\x1b'));
+        assert!(report.starts_with("```text\n####  \n  ####\n```\n"));
+        assert!(report.contains("| a | 0 | 2 | 2 | A | 1 |\n| b | 1 | 3 | 2 | B | 2 |\n"));
+    }
+
+    #[test]
+    fn test_wrap_width() {
+        let data: Vec<Bound> = vec![(0., 5.), (3., 12.), (15., 20.)];
+        let palette = Palette::new(vec![Color::Red], Assignment::FirstAppearance);
+        let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|_| Some("a".to_string()));
+        let rendered = renderer
+            .with_length(20)
+            .with_palette(palette, &|l| l.clone())
+            .with_wrap(Wrap::Width(10), &|(a, _)| format!("from {}", a))
+            .render_styled()
+            .unwrap();
+        let plain: Vec<Vec<String>> = rendered
+            .iter()
+            .map(|lines| lines.iter().map(|line| style::plain(line)).collect())
+            .collect();
+        assert_eq!(
+            plain,
+            vec![
+                vec!["from 0", "   ======»", "=====     "],
+                vec!["from 10", "«=   ====="],
+            ]
+        );
+        let continued = &rendered[1][1][0];
+        assert!(continued.text.starts_with('«'));
+        assert_eq!(continued.style.bg, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_calendar_intensity() {
+        const DAY: f64 = 86_400.;
+        // overlapping intervals count twice, Sunday 1970-01-04 is empty
+        let data: Vec<Bound> = vec![(0., DAY), (0., DAY), (DAY, 2. * DAY)];
+        let calendar = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>)
+            .with_boundaries((0., 4. * DAY))
+            .with_bound_format(&|_| "w".to_string())
+            .render_calendar(Shading::Intensity, CalendarTime::default())
+            .unwrap();
+        assert_eq!(
+            calendar,
+            vec!["  Mo Tu We Th Fr Sa Su", "w          ██ ▒▒ ·· ··"]
+        );
+        let empty: Vec<Bound> = vec![];
+        let result = Renderer::new(empty.as_slice(), &|&e| e, &|_| None::<String>)
+            .render_calendar(Shading::Coverage, CalendarTime::default());
+        assert_eq!(result, Err(TBLError::NoBoundaries));
+    }
+
+    struct BrokenPipe;
+
+    impl std::io::Write for BrokenPipe {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "closed",
+            ))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_render_to() {
+        let data: Vec<Bound> = vec![(0., 2.), (1., 3.), (3., 4.)];
+        let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>);
+        let renderer = renderer
+            .with_length(8)
+            .with_color_level(ColorLevel::Monochrome);
+        let mut out = String::new();
+        renderer.render_to_fmt(&mut out).unwrap();
+        let expected: Vec<String> = renderer.render().unwrap().into_iter().flatten().collect();
+        assert_eq!(out, expected.join("\n") + "\n");
+        assert!(matches!(
+            renderer.render_to(&mut BrokenPipe),
+            Err(TBLError::Io(std::io::ErrorKind::BrokenPipe, _))
+        ));
+    }
+
+    /// Accepts `limit` bytes then fails.
+    struct Limited {
+        written: Vec<u8>,
+        limit: usize,
+    }
+
+    impl std::io::Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.written.len() + buf.len() > self.limit {
+                return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "full"));
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_many_lanes_order() {
+        // every interval overlaps all others: one lane per interval
+        let data: Vec<Bound> = (0..500).map(|i| (i as f64, 1000. - i as f64)).collect();
+        let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>);
+        let renderer = renderer
+            .with_length(100)
+            .with_color_level(ColorLevel::Monochrome);
+        let rendered = renderer.render().unwrap();
+        assert_eq!(rendered.len(), 500);
+        let expected: String = rendered
+            .iter()
+            .flatten()
+            .map(|l| format!("{}\n", l))
+            .collect();
+        let mut out = Limited {
+            written: vec![],
+            limit: 101 * 200,
+        };
+        let result = renderer.render_to(&mut out);
+        assert!(matches!(
+            result,
+            Err(TBLError::Io(std::io::ErrorKind::WriteZero, _))
+        ));
+        assert_eq!(out.written, expected.as_bytes()[..101 * 200].to_vec());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use tbl::{Layout, Options, Segment};
+        let options: Options =
+            serde_json::from_str(r#"{"length": 6, "segment_style": "Box"}"#).unwrap();
+        assert_eq!(options.length, Some(6));
+        assert_eq!(options.segment_style, Some(SegmentStyle::Box));
+        let data: Vec<Segment<String>> = serde_json::from_str(
+            r#"[{"bounds": [0, 1], "label": "a"}, {"bounds": [2, 3], "label": null}]"#,
+        )
+        .unwrap();
+        let mut renderer = Renderer::new(data.as_slice(), &|s| s.bounds, &|s| s.label.clone());
+        let renderer = renderer.with_options(&options);
+        assert_eq!(renderer.render().unwrap(), vec![vec!["├┤  ├┤"]]);
+        let layout = renderer.layout();
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Layout<String>>(&json).unwrap(),
+            layout
+        );
+    }
+
+    #[test]
+    fn test_vertical_renderer_and_style() {
+        let data = vec![(0., 2., "ab"), (2., 3., "cd")];
+        let render = |b: &Block<String>| match b {
+            Block::Space(length) => RenderBlock::Space(" ".repeat(*length)),
+            Block::Segment(length, label) => {
+                let label = label.clone().unwrap_or_default();
+                RenderBlock::Block(format!("{:*<w$}", label, w = *length))
+            }
+        };
+        let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        });
+        let rendered = renderer
+            .with_length(3)
+            .with_orientation(Orientation::Vertical)
+            .with_lane_width(3)
+            .with_renderer(&render)
+            .render()
+            .unwrap();
+        assert_eq!(rendered, vec![vec!["ab*", "***", "cd*"]]);
+        let mut renderer = Renderer::new(data.as_slice(), &|&(a, b, _)| (a, b), &|&(_, _, l)| {
+            Some(l.to_string())
+        });
+        let rendered = renderer
+            .with_length(3)
+            .with_orientation(Orientation::Vertical)
+            .with_lane_width(3)
+            .with_segment_style(SegmentStyle::Box)
+            .render()
+            .unwrap();
+        // not labelled without label format
+        assert_eq!(rendered, vec![vec!["┬┬┬", "┴┴┴", "───"]]);
+    }
+
+    #[test]
+    fn test_vertical_caps() {
+        // touching segments
+        let data: Vec<Bound> = vec![(0., 3.), (3., 5.)];
+        let mut renderer = Renderer::new(data.as_slice(), &|&e| e, &|_| None::<String>);
+        let rendered = renderer
+            .with_length(5)
+            .with_orientation(Orientation::Vertical)
+            .with_lane_width(2)
+            .with_segment_style(SegmentStyle::Rounded)
+            .render()
+            .unwrap();
+        assert_eq!(rendered, vec![vec!["╷╷", "││", "╵╵", "╷╷", "╵╵"]]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_render_par() {
+        // non `Sync` closures
+        let prefix = std::cell::Cell::new("group");
+        let group = |l: &String| Some(format!("{} {}", prefix.get(), &l[..1]));
+        let data: Vec<(f64, f64, String)> = (0..100)
+            .map(|i| (i as f64, i as f64 + 7., format!("{}", i % 3)))
+            .collect();
+        let mut renderer = Renderer::new(data.as_slice(), &|(a, b, _)| (*a, *b), &|(_, _, l)| {
+            Some(l.clone())
+        });
+        let renderer = renderer
+            .with_length(50)
+            .with_groups(&group)
+            .with_color_level(ColorLevel::Monochrome);
+        assert_eq!(renderer.render_par(), renderer.render());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_render_par_lanes() {
+        // thousands of styled lanes
+        let data: Vec<(f64, f64, String)> = (0..4_000)
+            .map(|i| ((i % 10) as f64, 100. + (i % 7) as f64, format!("{}", i % 5)))
+            .collect();
+        let key = |l: &String| l.clone();
+        let mut renderer = Renderer::new(data.as_slice(), &|(a, b, _)| (*a, *b), &|(_, _, l)| {
+            Some(l.clone())
+        });
+        let renderer = renderer
+            .with_length(80)
+            .with_palette(
+                Palette::new(vec![Color::Red, Color::Blue], Assignment::FirstAppearance),
+                &key,
+            )
+            .with_patterns(Patterns::default(), &key)
+            .with_segment_style(SegmentStyle::Box)
+            .with_color_level(ColorLevel::TrueColor);
+        let rendered = renderer.render_par().unwrap();
+        assert_eq!(rendered.len(), 4_000);
+        assert_eq!(rendered, renderer.render().unwrap());
+    }
 }